@@ -1,3 +1,4 @@
+use selectel_mks::retry::RetryPolicy;
 use selectel_mks::Client;
 use std::time::Duration;
 
@@ -5,6 +6,9 @@ fn main() {
     // Configure custom timeout for a new client.
     let timeout_secs = 10;
 
+    // Configure custom retry policy for failed requests.
+    let retry_policy = RetryPolicy::new().with_max_retries(5);
+
     // Get endpoint for the needed region:
     //  - ru-1: https://ru-1.mks.selcloud.ru
     //  - ru-2: https://ru-2.mks.selcloud.ru
@@ -18,6 +22,7 @@ fn main() {
 
     let _client = Client::builder()
         .with_timeout(Duration::from_secs(timeout_secs))
+        .with_retry_policy(retry_policy)
         .build(endpoint, token)
         .expect("failed to initialize MKS client");
 }
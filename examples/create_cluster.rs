@@ -1,27 +1,30 @@
-use selectel_mks::Client;
-use selectel_mks::{cluster, nodegroup};
+use selectel_mks::auth::keystone::{KeystoneAuth, SELECTEL_AUTH_URL};
+use selectel_mks::auth::schemas::AuthOpts;
+use selectel_mks::region::Region;
+use selectel_mks::{cluster, nodegroup, Client};
 
 fn main() {
-    // Get endpoint for the needed region:
-    //  - ru-1: https://ru-1.mks.selcloud.ru
-    //  - ru-2: https://ru-2.mks.selcloud.ru
-    //  - ru-3: https://ru-3.mks.selcloud.ru
-    //  - ru-7: https://ru-7.mks.selcloud.ru
-    //  - ru-8: https://ru-8.mks.selcloud.ru
-    let endpoint = "https://ru-3.mks.selcloud.ru";
-
-    // Get project-scoped token value.
-    let token = "token_value";
+    // Prepare credentials of the service user and its project.
+    // Project-scoped tokens are issued and refreshed automatically.
+    let account_id = "123456";
+    let auth_opts =
+        AuthOpts::password("username", "password", account_id).with_project_id("project_id");
+    let auth =
+        KeystoneAuth::new(SELECTEL_AUTH_URL, auth_opts).expect("failed to prepare Keystone auth");
 
     // Initialize a new client.
-    let client = Client::new(endpoint, token).expect("failed to initialize MKS client");
+    // MKS endpoint for the region is discovered in the Keystone service catalog.
+    let region = Region::Ru3;
+    let client = Client::builder()
+        .build_blocking_with_keystone(auth, region.as_str())
+        .expect("failed to initialize MKS client");
 
     // Prepare nodegroup options.
     let nodes_count = 2;
     let local_volumes = false;
     let availability_zone = "ru-3a"; // should be available in the selected region
     let nodegroup_opts =
-        nodegroup::schemas::CreateOpts::new(nodes_count, local_volumes, &availability_zone)
+        nodegroup::schemas::CreateOpts::new(nodes_count, local_volumes, availability_zone)
             .with_cpus(2)
             .with_ram_mb(2048)
             .with_volume_gb(10)
@@ -30,8 +33,7 @@ fn main() {
     // Prepare cluster options.
     let name = "my-cluster";
     let kube_version = "1.17.9";
-    let region = "ru-3";
-    let cluster_opts = cluster::schemas::CreateOpts::new(name, &kube_version, &region)
+    let cluster_opts = cluster::schemas::CreateOpts::new(name, kube_version, region.as_str())
         .with_nodegroups(vec![nodegroup_opts]);
 
     // Create a new cluster.
@@ -1,6 +1,7 @@
 use selectel_mks::Client;
 
-fn main() {
+#[tokio::main]
+async fn main() {
     // Get endpoint for the needed region:
     //  - ru-1: https://ru-1.mks.selcloud.ru
     //  - ru-2: https://ru-2.mks.selcloud.ru
@@ -18,6 +19,7 @@ fn main() {
     // Get all Kubernetes versions.
     let kube_versions = client
         .list_kube_versions()
+        .await
         .expect("failed to list Kubernetes versions");
 
     println!("Kubernetes versions: {:?}\n", kube_versions);
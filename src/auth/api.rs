@@ -0,0 +1,88 @@
+use hyper::header::{HeaderValue, CONTENT_TYPE, USER_AGENT};
+use hyper::{Body, Method, Request};
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+use url::Url;
+
+use super::super::error::{ApiError, Error};
+use super::super::resource_url::AUTH_TOKENS;
+use super::super::transport::Transport;
+use super::super::Client;
+use super::schemas;
+
+// Response header with the issued token.
+const SUBJECT_TOKEN_HEADER: &str = "x-subject-token";
+
+// Path that is recorded in spans of Keystone requests.
+const AUTH_TOKENS_PATH: &str = "/auth/tokens";
+
+api_call! {
+    name = "mks.auth.authenticate",
+    skip(transport, opts),
+    pub async fn authenticate(
+        transport: &dyn Transport,
+        auth_url: &Url,
+        opts: &schemas::AuthOpts,
+        request_timeout: Duration,
+    ) -> Result<schemas::Token, Error> {
+        let root_opts = schemas::AuthOptsRoot { auth: opts };
+        let serialized = serde_json::to_string(&root_opts).map_err(Error::SerializeError)?;
+
+        let uri = auth_url
+            .join(AUTH_TOKENS)
+            .map_err(Error::UrlError)?
+            .as_str()
+            .parse::<hyper::Uri>()
+            .map_err(Error::UriError)?;
+        let mut req = Request::new(Body::from(serialized));
+        *req.method_mut() = Method::POST;
+        *req.uri_mut() = uri;
+        req.headers_mut().insert(
+            USER_AGENT,
+            HeaderValue::from_str(&Client::user_agent()).map_err(Error::RequestError)?,
+        );
+        req.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let span = tracing::Span::current();
+        span.record("method", "POST");
+        span.record("path", AUTH_TOKENS_PATH);
+        span.record("retries", 0);
+
+        let started = Instant::now();
+        let resp = timeout(request_timeout, transport.send(req)).await??;
+        let status = resp.status();
+        span.record("latency_ms", started.elapsed().as_millis() as u64);
+        span.record("status", status.as_u16());
+
+        let token_id = resp
+            .headers()
+            .get(SUBJECT_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+        let headers = resp.headers().clone();
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        let body = String::from_utf8_lossy(&body).to_string();
+
+        if !status.is_success() {
+            return Err(Error::HttpError(ApiError::from_response(
+                status.as_u16(),
+                &headers,
+                body,
+            )));
+        }
+
+        let token_id = token_id.ok_or_else(|| {
+            Error::AuthError(String::from(
+                "response doesn't contain X-Subject-Token header",
+            ))
+        })?;
+        let deserialized: schemas::TokenRoot =
+            serde_json::from_str(&body).map_err(|err| Error::DeserializeError(err, body))?;
+
+        let mut token = deserialized.token;
+        token.id = token_id;
+
+        Ok(token)
+    }
+}
@@ -0,0 +1,341 @@
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use url::Url;
+
+use super::super::error::Error;
+use super::super::token::{RefreshFuture, TokenFuture, TokenProvider};
+use super::super::transport::{HyperTransport, Transport};
+use super::super::DEFAULT_TIMEOUT;
+use super::api;
+use super::schemas::{AuthOpts, Token};
+
+/// Keystone v3 endpoint of the Selectel Cloud.
+pub const SELECTEL_AUTH_URL: &str = "https://cloud.api.selcloud.ru/identity/v3/";
+
+/// Type of the MKS service in the Keystone service catalog.
+pub const MKS_SERVICE_TYPE: &str = "mks";
+
+// Cached tokens that expire sooner are renewed before they are used.
+const EXPIRY_MARGIN_SECS: i64 = 300;
+
+/// `KeystoneAuth` exchanges credentials for a token with the Keystone v3 API.
+///
+/// It's a `TokenProvider` that caches the issued token and renews it
+/// before it expires or after it has been rejected.
+pub struct KeystoneAuth {
+    auth_url: Url,
+    opts: AuthOpts,
+    transport: Option<Arc<dyn Transport>>,
+    timeout: Duration,
+    service_type: String,
+    token: Mutex<Option<Token>>,
+}
+
+impl KeystoneAuth {
+    /// Construct the new KeystoneAuth for the Keystone v3 endpoint.
+    ///
+    /// Use `SELECTEL_AUTH_URL` for the Selectel Cloud.
+    pub fn new(auth_url: &str, opts: AuthOpts) -> Result<KeystoneAuth, Error> {
+        let mut auth_url = Url::parse(auth_url).map_err(Error::EndpointError)?;
+        if !auth_url.path().ends_with('/') {
+            let path = format!("{}/", auth_url.path());
+            auth_url.set_path(&path);
+        }
+
+        Ok(KeystoneAuth {
+            auth_url,
+            opts,
+            transport: None,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT),
+            service_type: String::from(MKS_SERVICE_TYPE),
+            token: Mutex::new(None),
+        })
+    }
+
+    /// Set transport that will be used to send Keystone requests.
+    ///
+    /// By default the transport of the `Client` is used when the client is
+    /// built with `Builder::build_with_keystone`, otherwise a new `HyperTransport`.
+    pub fn with_transport<T: Transport + 'static>(mut self, transport: T) -> KeystoneAuth {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Set Keystone request timeout.
+    ///
+    /// Default is 30 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> KeystoneAuth {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set type of the MKS service in the service catalog.
+    ///
+    /// Default is `MKS_SERVICE_TYPE`.
+    pub fn with_service_type(mut self, service_type: &str) -> KeystoneAuth {
+        self.service_type = String::from(service_type);
+        self
+    }
+
+    // Use the provided transport if another one isn't set.
+    pub(crate) fn with_default_transport(mut self, transport: Arc<dyn Transport>) -> KeystoneAuth {
+        if self.transport.is_none() {
+            self.transport = Some(transport);
+        }
+        self
+    }
+
+    /// Get a new token and cache it.
+    pub async fn authenticate(&self) -> Result<Token, Error> {
+        let mut cached = self.token.lock().await;
+        let token = self.issue().await?;
+        *cached = Some(token.clone());
+
+        Ok(token)
+    }
+
+    /// Get a new token after the provided one has been rejected.
+    ///
+    /// The cached token is returned if it has already replaced the rejected one,
+    /// so concurrent requests that are rejected with the same token get a single new token.
+    pub async fn renew(&self, rejected: &str) -> Result<Token, Error> {
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref() {
+            if token.id != rejected && !expires_soon(token) {
+                return Ok(token.clone());
+            }
+        }
+
+        let token = self.issue().await?;
+        *cached = Some(token.clone());
+
+        Ok(token)
+    }
+
+    /// Get the cached token or a new one if it's missing or expires soon.
+    pub async fn token(&self) -> Result<Token, Error> {
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref() {
+            if !expires_soon(token) {
+                return Ok(token.clone());
+            }
+        }
+
+        let token = self.issue().await?;
+        *cached = Some(token.clone());
+
+        Ok(token)
+    }
+
+    /// Find URL of the MKS endpoint for the region in the token service catalog.
+    pub async fn endpoint(&self, region: &str) -> Result<String, Error> {
+        let token = self.token().await?;
+
+        token
+            .endpoint(&self.service_type, region)
+            .map(String::from)
+            .ok_or_else(|| {
+                Error::AuthError(format!(
+                    "no public {} endpoint for region {} in the service catalog",
+                    self.service_type, region
+                ))
+            })
+    }
+
+    async fn issue(&self) -> Result<Token, Error> {
+        match &self.transport {
+            Some(transport) => {
+                api::authenticate(transport.as_ref(), &self.auth_url, &self.opts, self.timeout)
+                    .await
+            }
+            None => {
+                let transport = HyperTransport::new();
+                api::authenticate(&transport, &self.auth_url, &self.opts, self.timeout).await
+            }
+        }
+    }
+}
+
+impl TokenProvider for KeystoneAuth {
+    fn token(&self) -> TokenFuture<'_> {
+        Box::pin(async move { Ok(KeystoneAuth::token(self).await?.id) })
+    }
+
+    fn refresh<'a>(&'a self, rejected: &'a str) -> RefreshFuture<'a> {
+        Box::pin(async move { Ok(Some(self.renew(rejected).await?.id)) })
+    }
+}
+
+// Check if the token must be renewed before it's used.
+fn expires_soon(token: &Token) -> bool {
+    (token.expires_at - Utc::now()).num_seconds() <= EXPIRY_MARGIN_SECS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retry::RetryPolicy;
+    use crate::Client;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response, Server, StatusCode};
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Local Keystone stand-in that also serves the MKS API.
+    //
+    // It issues "token_1", "token_2", etc. for the "user:secret" credentials
+    // and accepts only the second token in MKS requests.
+    async fn keystone_stand_in() -> String {
+        keystone_stand_in_with_counter(Arc::new(AtomicUsize::new(0))).await
+    }
+
+    // Local Keystone stand-in that counts issued tokens.
+    async fn keystone_stand_in_with_counter(issued: Arc<AtomicUsize>) -> String {
+        let make_svc = make_service_fn(move |_| {
+            let issued = issued.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    let issued = issued.clone();
+                    async move { Ok::<_, Infallible>(handle(req, &issued).await) }
+                }))
+            }
+        });
+
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_svc);
+        let url = format!("http://{}", server.local_addr());
+        tokio::spawn(server);
+
+        url
+    }
+
+    async fn handle(req: Request<Body>, issued: &AtomicUsize) -> Response<Body> {
+        let host = req.headers()["host"].to_str().unwrap().to_string();
+        let path = req.uri().path().to_string();
+
+        match path.as_str() {
+            "/identity/v3/auth/tokens" => {
+                let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+                let opts: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                let user = &opts["auth"]["identity"]["password"]["user"];
+                if user["name"] != "user" || user["password"] != "secret" {
+                    return Response::builder()
+                        .status(StatusCode::UNAUTHORIZED)
+                        .body(Body::from(
+                            r#"{"error": {"code": 401, "message": "The request you have made requires authentication."}}"#,
+                        ))
+                        .unwrap();
+                }
+                assert_eq!(opts["auth"]["scope"]["project"]["id"], "project_a");
+
+                let token = format!("token_{}", issued.fetch_add(1, Ordering::SeqCst) + 1);
+                let body = serde_json::json!({
+                    "token": {
+                        "expires_at": "2100-01-01T00:00:00.000000Z",
+                        "project": {"id": "project_a", "name": "project"},
+                        "catalog": [{
+                            "type": "mks",
+                            "name": "mks",
+                            "endpoints": [
+                                {"interface": "internal", "region_id": "ru-1", "url": "http://internal"},
+                                {"interface": "public", "region_id": "ru-1", "url": format!("http://{}/mks", host)},
+                                {"interface": "public", "region_id": "ru-3", "url": "http://ru-3"}
+                            ]
+                        }]
+                    }
+                });
+
+                Response::builder()
+                    .status(StatusCode::CREATED)
+                    .header("x-subject-token", token)
+                    .body(Body::from(body.to_string()))
+                    .unwrap()
+            }
+            "/mks/v1/kubeversions" if req.headers()["x-auth-token"] == "token_2" => {
+                Response::new(Body::from(r#"{"kube_versions": []}"#))
+            }
+            "/mks/v1/kubeversions" => Response::builder()
+                .status(StatusCode::UNAUTHORIZED)
+                .body(Body::empty())
+                .unwrap(),
+            _ => Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::empty())
+                .unwrap(),
+        }
+    }
+
+    fn auth(url: &str, password: &str) -> KeystoneAuth {
+        let opts = AuthOpts::password("user", password, "domain").with_project_id("project_a");
+        KeystoneAuth::new(&format!("{}/identity/v3", url), opts).unwrap()
+    }
+
+    #[tokio::test]
+    async fn authenticate() {
+        let url = keystone_stand_in().await;
+        let auth = auth(&url, "secret");
+
+        let token = auth.authenticate().await.unwrap();
+        assert_eq!(token.id, "token_1");
+        assert!(!format!("{:?}", token).contains("token_1"));
+        assert_eq!(token.project.unwrap().id, "project_a");
+
+        // Cached token is reused.
+        assert_eq!(auth.token().await.unwrap().id, "token_1");
+        assert_eq!(auth.endpoint("ru-1").await.unwrap(), format!("{}/mks", url));
+        assert!(matches!(
+            auth.endpoint("ru-2").await,
+            Err(Error::AuthError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn renew_rejected_token_once() {
+        let issued = Arc::new(AtomicUsize::new(0));
+        let url = keystone_stand_in_with_counter(issued.clone()).await;
+        let auth = auth(&url, "secret");
+        assert_eq!(auth.token().await.unwrap().id, "token_1");
+
+        // Concurrent requests are rejected with the same token.
+        let refreshed =
+            futures::future::join_all((0..5).map(|_| TokenProvider::refresh(&auth, "token_1")))
+                .await;
+
+        for token in refreshed {
+            assert_eq!(token.unwrap().as_deref(), Some("token_2"));
+        }
+        assert_eq!(issued.load(Ordering::SeqCst), 2);
+
+        // A new token is issued if the cached one is rejected.
+        assert_eq!(auth.renew("token_2").await.unwrap().id, "token_3");
+    }
+
+    #[tokio::test]
+    async fn authenticate_with_bad_password() {
+        let url = keystone_stand_in().await;
+
+        let err = auth(&url, "bad").authenticate().await.unwrap_err();
+
+        match err {
+            Error::HttpError(err) => assert!(err.is_unauthorized()),
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_with_keystone() {
+        let url = keystone_stand_in().await;
+
+        let client = Client::builder()
+            .with_env_proxy(false)
+            .with_retry_policy(RetryPolicy::none())
+            .build_with_keystone(auth(&url, "secret"), "ru-1")
+            .await
+            .unwrap();
+
+        // The first token is rejected and the refreshed one is used.
+        let kube_versions = client.list_kube_versions().await.unwrap();
+        assert!(kube_versions.is_empty());
+    }
+}
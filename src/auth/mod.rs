@@ -0,0 +1,3 @@
+pub(crate) mod api;
+pub mod keystone;
+pub mod schemas;
@@ -0,0 +1,239 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Authentication options for the Keystone v3 tokens request.
+#[derive(Clone, Debug, Serialize)]
+pub struct AuthOpts {
+    identity: IdentityOpts,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scope: Option<ScopeOpts>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct IdentityOpts {
+    methods: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<PasswordOpts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    application_credential: Option<ApplicationCredentialOpts>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct PasswordOpts {
+    user: UserOpts,
+}
+
+#[derive(Clone, Serialize)]
+struct UserOpts {
+    name: String,
+    domain: DomainOpts,
+    password: String,
+}
+
+// Secrets are never printed.
+impl std::fmt::Debug for UserOpts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserOpts")
+            .field("name", &self.name)
+            .field("domain", &self.domain)
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct DomainOpts {
+    name: String,
+}
+
+#[derive(Clone, Serialize)]
+struct ApplicationCredentialOpts {
+    id: String,
+    secret: String,
+}
+
+impl std::fmt::Debug for ApplicationCredentialOpts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApplicationCredentialOpts")
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ScopeOpts {
+    project: ProjectOpts,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ProjectOpts {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    domain: Option<DomainOpts>,
+}
+
+impl AuthOpts {
+    /// Authenticate with the name and password of the user from the domain.
+    ///
+    /// Add a project scope to get a project-scoped token.
+    pub fn password(username: &str, password: &str, user_domain_name: &str) -> AuthOpts {
+        AuthOpts {
+            identity: IdentityOpts {
+                methods: vec!["password"],
+                password: Some(PasswordOpts {
+                    user: UserOpts {
+                        name: String::from(username),
+                        domain: DomainOpts {
+                            name: String::from(user_domain_name),
+                        },
+                        password: String::from(password),
+                    },
+                }),
+                application_credential: None,
+            },
+            scope: None,
+        }
+    }
+
+    /// Authenticate with the application credential.
+    ///
+    /// Application credential tokens are always scoped to its project.
+    pub fn application_credential(id: &str, secret: &str) -> AuthOpts {
+        AuthOpts {
+            identity: IdentityOpts {
+                methods: vec!["application_credential"],
+                password: None,
+                application_credential: Some(ApplicationCredentialOpts {
+                    id: String::from(id),
+                    secret: String::from(secret),
+                }),
+            },
+            scope: None,
+        }
+    }
+
+    /// Add a project scope by the project identifier.
+    pub fn with_project_id(mut self, project_id: &str) -> AuthOpts {
+        self.scope = Some(ScopeOpts {
+            project: ProjectOpts {
+                id: Some(String::from(project_id)),
+                name: None,
+                domain: None,
+            },
+        });
+        self
+    }
+
+    /// Add a project scope by the project name and its domain name.
+    pub fn with_project_name(mut self, project_name: &str, domain_name: &str) -> AuthOpts {
+        self.scope = Some(ScopeOpts {
+            project: ProjectOpts {
+                id: None,
+                name: Some(String::from(project_name)),
+                domain: Some(DomainOpts {
+                    name: String::from(domain_name),
+                }),
+            },
+        });
+        self
+    }
+}
+
+/// AuthOptsRoot represents a root of authentication options.
+#[derive(Debug, Serialize)]
+pub struct AuthOptsRoot<'a> {
+    pub auth: &'a AuthOpts,
+}
+
+/// Token represents a deserialized Keystone token from an API response.
+#[derive(Clone, Deserialize)]
+pub struct Token {
+    /// Token value from the X-Subject-Token header.
+    #[serde(skip)]
+    pub id: String,
+
+    /// Token expiration timestamp.
+    pub expires_at: DateTime<Utc>,
+
+    /// Project of the project-scoped token.
+    pub project: Option<Project>,
+
+    /// Service catalog of the token.
+    #[serde(default)]
+    pub catalog: Vec<CatalogEntry>,
+}
+
+impl std::fmt::Debug for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Token")
+            .field("expires_at", &self.expires_at)
+            .field("project", &self.project)
+            .field("catalog", &self.catalog)
+            .finish()
+    }
+}
+
+impl Token {
+    /// Find URL of the public service endpoint in the region.
+    pub fn endpoint(&self, service_type: &str, region: &str) -> Option<&str> {
+        self.catalog
+            .iter()
+            .filter(|entry| entry.service_type == service_type)
+            .flat_map(|entry| &entry.endpoints)
+            .find(|endpoint| {
+                endpoint.interface == "public"
+                    && (endpoint.region_id.as_deref() == Some(region)
+                        || endpoint.region.as_deref() == Some(region))
+            })
+            .map(|endpoint| endpoint.url.as_str())
+    }
+}
+
+/// Project represents a deserialized project of the token.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Project {
+    /// Project identifier.
+    pub id: String,
+
+    /// Project name.
+    pub name: String,
+}
+
+/// CatalogEntry represents a deserialized service of the token catalog.
+#[derive(Clone, Debug, Deserialize)]
+pub struct CatalogEntry {
+    /// Service type, for example "mks".
+    #[serde(rename = "type")]
+    pub service_type: String,
+
+    /// Service name.
+    #[serde(default)]
+    pub name: String,
+
+    /// Service endpoints.
+    pub endpoints: Vec<Endpoint>,
+}
+
+/// Endpoint represents a deserialized service endpoint of the token catalog.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Endpoint {
+    /// Endpoint interface: "public", "internal" or "admin".
+    pub interface: String,
+
+    /// Endpoint region.
+    pub region: Option<String>,
+
+    /// Endpoint region identifier.
+    pub region_id: Option<String>,
+
+    /// Endpoint URL.
+    pub url: String,
+}
+
+/// TokenRoot represents a root of a deserialized token.
+#[derive(Debug, Deserialize)]
+pub struct TokenRoot {
+    pub token: Token,
+}
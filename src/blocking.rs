@@ -0,0 +1,547 @@
+//! Synchronous wrapper around the asynchronous `Client`.
+//!
+//! This module is available with the `blocking` feature which is enabled by default.
+//!
+//! Every method blocks the current thread until the request is finished.
+//! It must not be called from inside of an async runtime; use the
+//! asynchronous `selectel_mks::Client` there instead.
+
+use hyper::body::Bytes;
+use hyper::Method;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::future::Future;
+use tokio::runtime::Runtime;
+
+use super::auth::keystone::KeystoneAuth;
+use super::error::Error;
+use super::multi_region::{ClusterSearch, RegionResults};
+use super::region::Region;
+use super::response::Response;
+use super::{cluster, kubeversion, node, nodegroup, task, Builder};
+
+/// `Client` struct is used to make blocking calls to the MKS API.
+///
+/// It owns a single runtime that is reused by all calls during the whole
+/// lifetime of the client. It's safe to share the client between threads.
+pub struct Client {
+    inner: super::Client,
+    runtime: Runtime,
+}
+
+impl Client {
+    /// Construct the new blocking Client struct with default configuration.
+    ///
+    /// Use `Builder::build_blocking` to configure the client.
+    pub fn new(base_endpoint: &str, token: &str) -> Result<Client, Error> {
+        Client::from_async(super::Client::new(base_endpoint, token)?)
+    }
+
+    /// Construct the new blocking Client struct for the region with default configuration.
+    pub fn for_region(region: Region, token: &str) -> Result<Client, Error> {
+        Client::from_async(super::Client::for_region(region, token)?)
+    }
+
+    /// Construct the new blocking Client struct from the async one.
+    pub fn from_async(inner: super::Client) -> Result<Client, Error> {
+        let runtime = Client::runtime()?;
+
+        Ok(Client { inner, runtime })
+    }
+
+    /// Get client that returns responses with the HTTP status, headers and request ID.
+    pub fn with_metadata(&self) -> MetadataClient<'_> {
+        MetadataClient { client: self }
+    }
+
+    // Build the async Client with Keystone auth on the new client runtime.
+    pub(crate) fn with_keystone(
+        builder: Builder,
+        auth: KeystoneAuth,
+        region: &str,
+    ) -> Result<Client, Error> {
+        let runtime = Client::runtime()?;
+        let inner = runtime
+            .handle()
+            .block_on(builder.build_with_keystone(auth, region))?;
+
+        Ok(Client { inner, runtime })
+    }
+
+    fn runtime() -> Result<Runtime, Error> {
+        tokio::runtime::Builder::new()
+            .threaded_scheduler()
+            .enable_all()
+            .thread_name(format!("{}-blocking", super::PKG_NAME))
+            .build()
+            .map_err(Error::RuntimeError)
+    }
+
+    // Run the provided future to completion on the client runtime.
+    fn block_on<F, T>(&self, future: F) -> Result<T, Error>
+    where
+        F: Future<Output = Result<T, Error>>,
+    {
+        self.runtime.handle().block_on(future)
+    }
+}
+
+/// Methods to work with clusters.
+impl Client {
+    /// Get a cluster.
+    pub fn get_cluster(&self, cluster_id: &str) -> Result<cluster::schemas::Cluster, Error> {
+        self.block_on(self.inner.get_cluster(cluster_id))
+    }
+
+    /// List clusters.
+    pub fn list_clusters(&self) -> Result<Vec<cluster::schemas::Cluster>, Error> {
+        self.block_on(self.inner.list_clusters())
+    }
+
+    /// Create a cluster.
+    pub fn create_cluster(
+        &self,
+        opts: &cluster::schemas::CreateOpts,
+    ) -> Result<cluster::schemas::Cluster, Error> {
+        self.block_on(self.inner.create_cluster(opts))
+    }
+
+    /// Delete a cluster.
+    pub fn delete_cluster(&self, cluster_id: &str) -> Result<(), Error> {
+        self.block_on(self.inner.delete_cluster(cluster_id))
+    }
+}
+
+/// Methods to work with Kubernetes versions.
+impl Client {
+    /// List all Kubernetes versions.
+    pub fn list_kube_versions(&self) -> Result<Vec<kubeversion::schemas::KubeVersion>, Error> {
+        self.block_on(self.inner.list_kube_versions())
+    }
+}
+
+/// Methods to work with nodes.
+impl Client {
+    /// Get a cluster node.
+    pub fn get_node(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        node_id: &str,
+    ) -> Result<node::schemas::Node, Error> {
+        self.block_on(self.inner.get_node(cluster_id, nodegroup_id, node_id))
+    }
+
+    /// Reinstall a cluster node.
+    pub fn reinstall_node(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        node_id: &str,
+    ) -> Result<(), Error> {
+        self.block_on(self.inner.reinstall_node(cluster_id, nodegroup_id, node_id))
+    }
+}
+
+/// Methods to work with nodegroups.
+impl Client {
+    /// Get a cluster nodegroup.
+    pub fn get_nodegroup(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+    ) -> Result<nodegroup::schemas::Nodegroup, Error> {
+        self.block_on(self.inner.get_nodegroup(cluster_id, nodegroup_id))
+    }
+
+    /// List cluster nodegroups.
+    pub fn list_nodegroups(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Vec<nodegroup::schemas::Nodegroup>, Error> {
+        self.block_on(self.inner.list_nodegroups(cluster_id))
+    }
+
+    /// Create a cluster nodegroup.
+    pub fn create_nodegroup(
+        &self,
+        cluster_id: &str,
+        opts: &nodegroup::schemas::CreateOpts,
+    ) -> Result<(), Error> {
+        self.block_on(self.inner.create_nodegroup(cluster_id, opts))
+    }
+
+    /// Delete a cluster nodegroup.
+    pub fn delete_nodegroup(&self, cluster_id: &str, nodegroup_id: &str) -> Result<(), Error> {
+        self.block_on(self.inner.delete_nodegroup(cluster_id, nodegroup_id))
+    }
+
+    /// Resize a cluster nodegroup.
+    pub fn resize_nodegroup(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        opts: &nodegroup::schemas::ResizeOpts,
+    ) -> Result<(), Error> {
+        self.block_on(self.inner.resize_nodegroup(cluster_id, nodegroup_id, opts))
+    }
+
+    /// Update a cluster nodegroup.
+    pub fn update_nodegroup(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        opts: &nodegroup::schemas::UpdateOpts,
+    ) -> Result<(), Error> {
+        self.block_on(self.inner.update_nodegroup(cluster_id, nodegroup_id, opts))
+    }
+}
+
+/// Methods to work with tasks.
+impl Client {
+    /// Get a task.
+    pub fn get_task(&self, cluster_id: &str, task_id: &str) -> Result<task::schemas::Task, Error> {
+        self.block_on(self.inner.get_task(cluster_id, task_id))
+    }
+
+    /// List tasks.
+    pub fn list_tasks(&self, cluster_id: &str) -> Result<Vec<task::schemas::Task>, Error> {
+        self.block_on(self.inner.list_tasks(cluster_id))
+    }
+}
+
+/// Methods to send requests that aren't wrapped by the client yet.
+impl Client {
+    /// Send a request to the MKS API and deserialize the JSON response body.
+    ///
+    /// See `selectel_mks::Client::request` for details.
+    pub fn request<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T, Error> {
+        self.block_on(self.inner.request(method, path, body))
+    }
+
+    /// Send a request to the MKS API and get the raw response body.
+    ///
+    /// See `selectel_mks::Client::request` for details.
+    pub fn request_raw<B: Serialize>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<Bytes, Error> {
+        self.block_on(self.inner.request_raw(method, path, body))
+    }
+}
+
+/// `MetadataClient` makes the same blocking calls as `Client` but returns responses with metadata.
+///
+/// Use `Client::with_metadata` to get it.
+pub struct MetadataClient<'a> {
+    client: &'a Client,
+}
+
+/// Methods to work with clusters.
+impl MetadataClient<'_> {
+    /// Get a cluster.
+    pub fn get_cluster(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Response<cluster::schemas::Cluster>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client.block_on(inner.get_cluster(cluster_id))
+    }
+
+    /// List clusters.
+    pub fn list_clusters(&self) -> Result<Response<Vec<cluster::schemas::Cluster>>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client.block_on(inner.list_clusters())
+    }
+
+    /// Create a cluster.
+    pub fn create_cluster(
+        &self,
+        opts: &cluster::schemas::CreateOpts,
+    ) -> Result<Response<cluster::schemas::Cluster>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client.block_on(inner.create_cluster(opts))
+    }
+
+    /// Delete a cluster.
+    pub fn delete_cluster(&self, cluster_id: &str) -> Result<Response<()>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client.block_on(inner.delete_cluster(cluster_id))
+    }
+}
+
+/// Methods to work with Kubernetes versions.
+impl MetadataClient<'_> {
+    /// List all Kubernetes versions.
+    pub fn list_kube_versions(
+        &self,
+    ) -> Result<Response<Vec<kubeversion::schemas::KubeVersion>>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client.block_on(inner.list_kube_versions())
+    }
+}
+
+/// Methods to work with nodes.
+impl MetadataClient<'_> {
+    /// Get a cluster node.
+    pub fn get_node(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        node_id: &str,
+    ) -> Result<Response<node::schemas::Node>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client
+            .block_on(inner.get_node(cluster_id, nodegroup_id, node_id))
+    }
+
+    /// Reinstall a cluster node.
+    pub fn reinstall_node(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        node_id: &str,
+    ) -> Result<Response<()>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client
+            .block_on(inner.reinstall_node(cluster_id, nodegroup_id, node_id))
+    }
+}
+
+/// Methods to work with nodegroups.
+impl MetadataClient<'_> {
+    /// Get a cluster nodegroup.
+    pub fn get_nodegroup(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+    ) -> Result<Response<nodegroup::schemas::Nodegroup>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client
+            .block_on(inner.get_nodegroup(cluster_id, nodegroup_id))
+    }
+
+    /// List cluster nodegroups.
+    pub fn list_nodegroups(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Response<Vec<nodegroup::schemas::Nodegroup>>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client.block_on(inner.list_nodegroups(cluster_id))
+    }
+
+    /// Create a cluster nodegroup.
+    pub fn create_nodegroup(
+        &self,
+        cluster_id: &str,
+        opts: &nodegroup::schemas::CreateOpts,
+    ) -> Result<Response<()>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client
+            .block_on(inner.create_nodegroup(cluster_id, opts))
+    }
+
+    /// Delete a cluster nodegroup.
+    pub fn delete_nodegroup(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+    ) -> Result<Response<()>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client
+            .block_on(inner.delete_nodegroup(cluster_id, nodegroup_id))
+    }
+
+    /// Resize a cluster nodegroup.
+    pub fn resize_nodegroup(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        opts: &nodegroup::schemas::ResizeOpts,
+    ) -> Result<Response<()>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client
+            .block_on(inner.resize_nodegroup(cluster_id, nodegroup_id, opts))
+    }
+
+    /// Update a cluster nodegroup.
+    pub fn update_nodegroup(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        opts: &nodegroup::schemas::UpdateOpts,
+    ) -> Result<Response<()>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client
+            .block_on(inner.update_nodegroup(cluster_id, nodegroup_id, opts))
+    }
+}
+
+/// Methods to work with tasks.
+impl MetadataClient<'_> {
+    /// Get a task.
+    pub fn get_task(
+        &self,
+        cluster_id: &str,
+        task_id: &str,
+    ) -> Result<Response<task::schemas::Task>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client.block_on(inner.get_task(cluster_id, task_id))
+    }
+
+    /// List tasks.
+    pub fn list_tasks(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Response<Vec<task::schemas::Task>>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client.block_on(inner.list_tasks(cluster_id))
+    }
+}
+
+/// Methods to send requests that aren't wrapped by the client yet.
+impl MetadataClient<'_> {
+    /// Send a request to the MKS API and deserialize the JSON response body.
+    ///
+    /// See `selectel_mks::Client::request` for details.
+    pub fn request<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<Response<T>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client.block_on(inner.request(method, path, body))
+    }
+
+    /// Send a request to the MKS API and get the raw response body.
+    ///
+    /// See `selectel_mks::Client::request` for details.
+    pub fn request_raw<B: Serialize>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<Response<Bytes>, Error> {
+        let inner = self.client.inner.with_metadata();
+        self.client.block_on(inner.request_raw(method, path, body))
+    }
+}
+
+/// `MultiRegionClient` struct is used to make blocking calls to the MKS API in several regions.
+///
+/// Calls are made in all regions concurrently on the runtime of the client,
+/// see `selectel_mks::multi_region::MultiRegionClient` for details.
+pub struct MultiRegionClient {
+    inner: super::multi_region::MultiRegionClient,
+    runtime: Runtime,
+}
+
+impl MultiRegionClient {
+    /// Construct the new blocking MultiRegionClient with default clients for the provided regions.
+    ///
+    /// Use `Builder::build_blocking_multi_region` to configure clients.
+    pub fn new(regions: &[Region], token: &str) -> Result<MultiRegionClient, Error> {
+        MultiRegionClient::from_async(super::multi_region::MultiRegionClient::new(regions, token)?)
+    }
+
+    /// Construct the new blocking MultiRegionClient from the async one.
+    pub fn from_async(
+        inner: super::multi_region::MultiRegionClient,
+    ) -> Result<MultiRegionClient, Error> {
+        let runtime = Client::runtime()?;
+
+        Ok(MultiRegionClient { inner, runtime })
+    }
+
+    /// Get regions of the client.
+    pub fn regions(&self) -> Vec<Region> {
+        self.inner.regions()
+    }
+
+    /// List clusters in all regions.
+    pub fn list_clusters_all(&self) -> RegionResults<Vec<cluster::schemas::Cluster>> {
+        self.runtime
+            .handle()
+            .block_on(self.inner.list_clusters_all())
+    }
+
+    /// Find a cluster by its identifier in all regions.
+    ///
+    /// See `selectel_mks::multi_region::MultiRegionClient::find_cluster` for details.
+    pub fn find_cluster(&self, cluster_id: &str) -> ClusterSearch {
+        self.runtime
+            .handle()
+            .block_on(self.inner.find_cluster(cluster_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::InMemoryTransport;
+
+    #[test]
+    fn new_blocking_client_with_builder() {
+        let client = Builder::default()
+            .build_blocking("https://example.com", "token_b")
+            .unwrap();
+
+        let token = client
+            .block_on(client.inner.token_provider.token())
+            .unwrap();
+        assert_eq!(token, "token_b");
+    }
+
+    #[test]
+    fn blocking_response_with_metadata() {
+        let transport = InMemoryTransport::new().with_http_response(
+            hyper::Response::builder()
+                .status(204)
+                .header("x-request-id", "req-2")
+                .body(hyper::body::Bytes::new())
+                .unwrap(),
+        );
+        let client = Builder::default()
+            .with_transport(transport)
+            .build_blocking("https://example.com", "token_b")
+            .unwrap();
+
+        let resp = client.with_metadata().delete_cluster("dbe7559b").unwrap();
+
+        assert_eq!(resp.status(), hyper::StatusCode::NO_CONTENT);
+        assert_eq!(resp.request_id(), Some("req-2"));
+    }
+
+    #[test]
+    fn blocking_multi_region_client() {
+        let client = |status, body| {
+            Builder::default()
+                .with_transport(InMemoryTransport::new().with_response(status, body))
+                .with_retry_policy(crate::retry::RetryPolicy::none())
+                .build("https://example.com", "token_b")
+                .unwrap()
+        };
+        let inner = super::super::multi_region::MultiRegionClient::from_clients(vec![
+            (Region::Ru1, client(200, r#"{"clusters": []}"#)),
+            (Region::Ru3, client(500, "")),
+        ]);
+        let multi_region = MultiRegionClient::from_async(inner).unwrap();
+
+        let results = multi_region.list_clusters_all();
+
+        assert_eq!(multi_region.regions(), vec![Region::Ru1, Region::Ru3]);
+        let successes: Vec<_> = results.successes().map(|(region, _)| region).collect();
+        assert_eq!(successes, vec![&Region::Ru1]);
+        let failures: Vec<_> = results.failures().collect();
+        assert_eq!(failures[0].0, &Region::Ru3);
+        assert_eq!(failures[0].1.status_code(), Some(500));
+    }
+}
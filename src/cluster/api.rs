@@ -1,50 +1,216 @@
 use hyper::Method;
 
 use super::super::error::Error;
-use super::super::resource_url::{API_VERSION, CLUSTERS};
+use super::super::resource_url::{expand, CLUSTERS_PATH, CLUSTER_PATH};
+use super::super::response::Response;
 use super::super::Client;
 use super::schemas;
 
-pub fn get(client: &Client, cluster_id: &str) -> Result<schemas::Cluster, Error> {
-    let path = format!("/{}/{}/{}", API_VERSION, CLUSTERS, cluster_id);
-    let req = client.new_request(Method::GET, &path, None)?;
-    let body = client.do_request(req)?;
+api_call! {
+    name = "mks.cluster.get",
+    skip(client),
+    pub async fn get(
+        client: &Client,
+        cluster_id: &str,
+    ) -> Result<Response<schemas::Cluster>, Error> {
+        let path = expand(CLUSTER_PATH, &[cluster_id])?;
+        let req = client.new_request(Method::GET, &path, None)?;
+        let deserialized: Response<schemas::ClusterRoot> =
+            client.do_request_json(req, CLUSTER_PATH).await?;
 
-    let deserialized: schemas::ClusterRoot =
-        serde_json::from_str(&body).map_err(|err| Error::DeserializeError(err, body))?;
+        Ok(deserialized.map(|root| root.cluster))
+    }
+}
+
+api_call! {
+    name = "mks.cluster.list",
+    skip(client),
+    pub async fn list(client: &Client) -> Result<Response<Vec<schemas::Cluster>>, Error> {
+        let path = expand(CLUSTERS_PATH, &[])?;
+        let req = client.new_request(Method::GET, &path, None)?;
+        let deserialized: Response<schemas::ListRoot> =
+            client.do_request_json(req, CLUSTERS_PATH).await?;
 
-    Ok(deserialized.cluster)
+        Ok(deserialized.map(|root| root.clusters))
+    }
 }
 
-pub fn list(client: &Client) -> Result<Vec<schemas::Cluster>, Error> {
-    let path = format!("/{}/{}", API_VERSION, CLUSTERS);
-    let req = client.new_request(Method::GET, &path, None)?;
-    let body = client.do_request(req)?;
+api_call! {
+    name = "mks.cluster.create",
+    skip(client, opts),
+    pub async fn create(
+        client: &Client,
+        opts: &schemas::CreateOpts,
+    ) -> Result<Response<schemas::Cluster>, Error> {
+        let root_opts = schemas::CreateOptsRoot { cluster: opts };
+        let serialized = serde_json::to_string(&root_opts).map_err(Error::SerializeError)?;
 
-    let deserialized: schemas::ListRoot =
-        serde_json::from_str(&body).map_err(|err| Error::DeserializeError(err, body))?;
+        let path = expand(CLUSTERS_PATH, &[])?;
+        let req = client.new_request(Method::POST, &path, Some(serialized))?;
+        let deserialized: Response<schemas::ClusterRoot> =
+            client.do_request_json(req, CLUSTERS_PATH).await?;
 
-    Ok(deserialized.clusters)
+        Ok(deserialized.map(|root| root.cluster))
+    }
 }
 
-pub fn create(client: &Client, opts: &schemas::CreateOpts) -> Result<schemas::Cluster, Error> {
-    let root_opts = schemas::CreateOptsRoot { cluster: opts };
-    let serialized = serde_json::to_string(&root_opts).map_err(Error::SerializeError)?;
+api_call! {
+    name = "mks.cluster.delete",
+    skip(client),
+    pub async fn delete(client: &Client, cluster_id: &str) -> Result<Response<()>, Error> {
+        let path = expand(CLUSTER_PATH, &[cluster_id])?;
+        let req = client.new_request(Method::DELETE, &path, None)?;
+        let resp = client.do_request(req, CLUSTER_PATH).await?;
 
-    let path = format!("/{}/{}", API_VERSION, CLUSTERS);
-    let req = client.new_request(Method::POST, &path, Some(serialized))?;
-    let body = client.do_request(req)?;
+        Ok(resp.map(|_| ()))
+    }
+}
 
-    let deserialized: schemas::ClusterRoot =
-        serde_json::from_str(&body).map_err(|err| Error::DeserializeError(err, body))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::CLUSTER;
+    use crate::transport::InMemoryTransport;
+    use std::sync::Arc;
 
-    Ok(deserialized.cluster)
-}
+    fn client(transport: &Arc<InMemoryTransport>) -> Client {
+        Client::builder()
+            .with_transport(transport.clone())
+            .build("https://example.org", "token_a")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_cluster() {
+        let body = format!(r#"{{"cluster": {}}}"#, CLUSTER);
+        let transport = Arc::new(InMemoryTransport::new().with_response(200, &body));
+
+        let cluster = get(&client(&transport), "dbe7559b-55d8-4f65-9230-6a22b985ff73")
+            .await
+            .unwrap()
+            .into_body();
+
+        assert_eq!(cluster.id, "dbe7559b-55d8-4f65-9230-6a22b985ff73");
+        assert_eq!(cluster.name, "test-cluster");
+        assert_eq!(cluster.kube_version, "1.17.9");
+        assert_eq!(cluster.pki_tree_updated_at, None);
+        assert!(cluster.kubernetes_options.enable_pod_security_policy);
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(
+            requests[0].uri,
+            "https://example.org/v1/clusters/dbe7559b-55d8-4f65-9230-6a22b985ff73"
+        );
+        assert_eq!(requests[0].headers["x-auth-token"], "token_a");
+    }
+
+    #[tokio::test]
+    async fn get_missing_cluster() {
+        let body = r#"{"error": {"id": "req-1", "message": "cluster is not found"}}"#;
+        let transport = Arc::new(InMemoryTransport::new().with_response(404, body));
+
+        let err = get(&client(&transport), "missing").await.unwrap_err();
+
+        assert!(err.is_not_found());
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn list_clusters() {
+        let body = format!(r#"{{"clusters": [{}, {}]}}"#, CLUSTER, CLUSTER);
+        let transport = Arc::new(InMemoryTransport::new().with_response(200, &body));
+
+        let clusters = list(&client(&transport)).await.unwrap().into_body();
+
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(
+            transport.requests()[0].uri,
+            "https://example.org/v1/clusters"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_cluster_with_unknown_fields() {
+        let cluster = CLUSTER
+            .replacen(
+                "{",
+                r#"{"audit_logs": {"enabled": true}, "hibernated": false,"#,
+                1,
+            )
+            .replace(
+                r#""enable_pod_security_policy": true"#,
+                r#""enable_pod_security_policy": true, "feature_gates": ["TTLAfterFinished"]"#,
+            );
+        let body = format!(r#"{{"cluster": {}}}"#, cluster);
+        let transport = Arc::new(InMemoryTransport::new().with_response(200, &body));
+
+        let cluster = get(&client(&transport), "dbe7559b")
+            .await
+            .unwrap()
+            .into_body();
+
+        assert_eq!(cluster.extra.len(), 2);
+        assert_eq!(cluster.extra["hibernated"], false);
+        let serialized = serde_json::to_value(&cluster).unwrap();
+        assert_eq!(serialized["audit_logs"]["enabled"], true);
+        assert_eq!(serialized["hibernated"], false);
+        assert_eq!(
+            serialized["kubernetes_options"]["feature_gates"][0],
+            "TTLAfterFinished"
+        );
+        assert_eq!(serialized["name"], "test-cluster");
+    }
+
+    #[tokio::test]
+    async fn list_clusters_with_unknown_status() {
+        let unknown = CLUSTER.replace(r#""status": "ACTIVE""#, r#""status": "PENDING_HIBERNATE""#);
+        let body = format!(r#"{{"clusters": [{}, {}]}}"#, CLUSTER, unknown);
+        let transport = Arc::new(InMemoryTransport::new().with_response(200, &body));
+
+        let clusters = list(&client(&transport)).await.unwrap().into_body();
+
+        assert_eq!(clusters[0].status, schemas::Status::Active);
+        assert_eq!(
+            clusters[1].status,
+            schemas::Status::Other(String::from("PENDING_HIBERNATE"))
+        );
+        assert_eq!(clusters[1].status.to_string(), "PENDING_HIBERNATE");
+        let serialized = serde_json::to_value(&clusters[1]).unwrap();
+        assert_eq!(serialized["status"], "PENDING_HIBERNATE");
+    }
+
+    #[tokio::test]
+    async fn create_cluster() {
+        let body = format!(r#"{{"cluster": {}}}"#, CLUSTER);
+        let transport = Arc::new(InMemoryTransport::new().with_response(200, &body));
+        let opts = schemas::CreateOpts::new("test-cluster", "1.17.9", "ru-1").with_zonal(true);
+
+        let cluster = create(&client(&transport), &opts)
+            .await
+            .unwrap()
+            .into_body();
+
+        assert_eq!(cluster.name, "test-cluster");
+
+        let requests = transport.requests();
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].headers["content-type"], "application/json");
+        let sent: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(sent["cluster"]["name"], "test-cluster");
+        assert_eq!(sent["cluster"]["kube_version"], "1.17.9");
+        assert_eq!(sent["cluster"]["zonal"], true);
+    }
+
+    #[tokio::test]
+    async fn delete_cluster() {
+        let transport = Arc::new(InMemoryTransport::new().with_response(204, ""));
 
-pub fn delete(client: &Client, cluster_id: &str) -> Result<(), Error> {
-    let path = format!("/{}/{}/{}", API_VERSION, CLUSTERS, cluster_id);
-    let req = client.new_request(Method::DELETE, &path, None)?;
-    client.do_request(req)?;
+        delete(&client(&transport), "dbe7559b").await.unwrap();
 
-    Ok(())
+        let requests = transport.requests();
+        assert_eq!(requests[0].method, Method::DELETE);
+        assert_eq!(requests[0].uri, "https://example.org/v1/clusters/dbe7559b");
+    }
 }
@@ -1,11 +1,18 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
+use super::super::error::Error;
+use super::super::kubeversion::schemas::KubeSemver;
 use super::super::nodegroup;
+use super::super::schema::{join, CheckSchema, SchemaCheck};
 
 /// Status represents a enum with various cluster statuses.
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+///
+/// Values that are unknown to this version of the library are kept in `Other`
+/// when responses are deserialized, so the enum isn't `Copy`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
 pub enum Status {
     Active,
     PendingCreate,
@@ -23,35 +30,156 @@ pub enum Status {
     Maintenance,
     Error,
     Unknown,
+
+    /// Value that is unknown to this version of the library.
+    /// It never holds values of other variants, use `Status::from` to build it.
+    Other(String),
+}
+
+impl Status {
+    /// Get status value as it's returned by the MKS API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Status::Active => "ACTIVE",
+            Status::PendingCreate => "PENDING_CREATE",
+            Status::PendingUpdate => "PENDING_UPDATE",
+            Status::PendingUpgrade => "PENDING_UPGRADE",
+            Status::PendingRotateCerts => "PENDING_ROTATE_CERTS",
+            Status::PendingDelete => "PENDING_DELETE",
+            Status::PendingResize => "PENDING_RESIZE",
+            Status::PendingNodeReinstall => "PENDING_NODE_REINSTALL",
+            Status::PendingUpgradePatchVersion => "PENDING_UPGRADE_PATCH_VERSION",
+            Status::PendingUpgradeMinorVersion => "PENDING_UPGRADE_MINOR_VERSION",
+            Status::PendingUpdateNodegroup => "PENDING_UPDATE_NODEGROUP",
+            Status::PendingUpgradeMastersConfiguration => "PENDING_UPGRADE_MASTERS_CONFIGURATION",
+            Status::PendingUpgradeClusterConfiguration => "PENDING_UPGRADE_CLUSTER_CONFIGURATION",
+            Status::Maintenance => "MAINTENANCE",
+            Status::Error => "ERROR",
+            Status::Unknown => "UNKNOWN",
+            Status::Other(status) => status,
+        }
+    }
+}
+
+/// Helpers to check the cluster lifecycle.
+impl Status {
+    /// Get coarse phase of the status.
+    pub fn phase(&self) -> Phase {
+        match self {
+            Status::Active => Phase::Active,
+            Status::PendingCreate
+            | Status::PendingUpdate
+            | Status::PendingUpgrade
+            | Status::PendingRotateCerts
+            | Status::PendingDelete
+            | Status::PendingResize
+            | Status::PendingNodeReinstall
+            | Status::PendingUpgradePatchVersion
+            | Status::PendingUpgradeMinorVersion
+            | Status::PendingUpdateNodegroup
+            | Status::PendingUpgradeMastersConfiguration
+            | Status::PendingUpgradeClusterConfiguration => Phase::Pending,
+            Status::Maintenance => Phase::Maintenance,
+            Status::Error => Phase::Error,
+            Status::Unknown | Status::Other(_) => Phase::Unknown,
+        }
+    }
+
+    /// Check if an operation with the cluster is in progress.
+    pub fn is_pending(&self) -> bool {
+        self.phase() == Phase::Pending
+    }
+
+    /// Check if the cluster has reached a state that isn't changed without a new operation.
+    ///
+    /// Unknown statuses aren't terminal.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self.phase(), Phase::Active | Phase::Error)
+    }
+
+    /// Check if the cluster has failed.
+    pub fn is_error(&self) -> bool {
+        self.phase() == Phase::Error
+    }
+
+    /// Check if the cluster can be updated, upgraded or resized.
+    ///
+    /// MKS rejects such requests while another operation or maintenance is in progress.
+    pub fn accepts_mutations(&self) -> bool {
+        self.phase() == Phase::Active
+    }
 }
 
 impl std::fmt::Display for Status {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            Status::Active => "ACTIVE".fmt(f),
-            Status::PendingCreate => "PENDING_CREATE".fmt(f),
-            Status::PendingUpdate => "PENDING_UPDATE".fmt(f),
-            Status::PendingUpgrade => "PENDING_UPGRADE".fmt(f),
-            Status::PendingRotateCerts => "PENDING_ROTATE_CERTS".fmt(f),
-            Status::PendingDelete => "PENDING_DELETE".fmt(f),
-            Status::PendingResize => "PENDING_RESIZE".fmt(f),
-            Status::PendingNodeReinstall => "PENDING_NODE_REINSTALL".fmt(f),
-            Status::PendingUpgradePatchVersion => "PENDING_UPGRADE_PATCH_VERSION".fmt(f),
-            Status::PendingUpgradeMinorVersion => "PENDING_UPGRADE_MINOR_VERSION".fmt(f),
-            Status::PendingUpdateNodegroup => "PENDING_UPDATE_NODEGROUP".fmt(f),
-            Status::PendingUpgradeMastersConfiguration => {
-                "PENDING_UPGRADE_MASTERS_CONFIGURATION".fmt(f)
-            }
-            Status::PendingUpgradeClusterConfiguration => {
-                "PENDING_UPGRADE_CLUSTER_CONFIGURATION".fmt(f)
-            }
-            Status::Maintenance => "MAINTENANCE".fmt(f),
-            Status::Error => "ERROR".fmt(f),
-            Status::Unknown => "UNKNOWN".fmt(f),
+        self.as_str().fmt(f)
+    }
+}
+
+impl From<String> for Status {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "ACTIVE" => Status::Active,
+            "PENDING_CREATE" => Status::PendingCreate,
+            "PENDING_UPDATE" => Status::PendingUpdate,
+            "PENDING_UPGRADE" => Status::PendingUpgrade,
+            "PENDING_ROTATE_CERTS" => Status::PendingRotateCerts,
+            "PENDING_DELETE" => Status::PendingDelete,
+            "PENDING_RESIZE" => Status::PendingResize,
+            "PENDING_NODE_REINSTALL" => Status::PendingNodeReinstall,
+            "PENDING_UPGRADE_PATCH_VERSION" => Status::PendingUpgradePatchVersion,
+            "PENDING_UPGRADE_MINOR_VERSION" => Status::PendingUpgradeMinorVersion,
+            "PENDING_UPDATE_NODEGROUP" => Status::PendingUpdateNodegroup,
+            "PENDING_UPGRADE_MASTERS_CONFIGURATION" => Status::PendingUpgradeMastersConfiguration,
+            "PENDING_UPGRADE_CLUSTER_CONFIGURATION" => Status::PendingUpgradeClusterConfiguration,
+            "MAINTENANCE" => Status::Maintenance,
+            "ERROR" => Status::Error,
+            "UNKNOWN" => Status::Unknown,
+            _ => Status::Other(status),
         }
     }
 }
 
+impl FromStr for Status {
+    type Err = Error;
+
+    /// Parse status case-insensitively, unknown values are rejected with `Error::ValueError`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Status::from(s.trim().to_ascii_uppercase()) {
+            Status::Other(_) => Err(Error::ValueError(String::from(s))),
+            status => Ok(status),
+        }
+    }
+}
+
+impl From<Status> for String {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Other(status) => status,
+            status => String::from(status.as_str()),
+        }
+    }
+}
+
+/// Phase represents a coarse group of cluster statuses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// Cluster is ready.
+    Active,
+
+    /// Operation with the cluster is in progress.
+    Pending,
+
+    /// Cluster maintenance is in progress.
+    Maintenance,
+
+    /// Cluster has failed.
+    Error,
+
+    /// Status is unknown.
+    Unknown,
+}
+
 /// Cluster represents a deserialized cluster body from an API response.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Cluster {
@@ -117,6 +245,18 @@ pub struct Cluster {
     /// Additional Kubernetes-related options
     /// such as pod security policy, feature gates, etc.
     pub kubernetes_options: KubernetesOptions,
+
+    /// Fields of the response that aren't modelled by this struct.
+    /// They are kept as is when the struct is serialized back.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Cluster {
+    /// Parse the current Kubernetes version of the cluster.
+    pub fn kube_semver(&self) -> Result<KubeSemver, Error> {
+        self.kube_version.parse()
+    }
 }
 
 /// ClusterRoot represents a root of a deserialized cluster.
@@ -133,11 +273,29 @@ pub struct ListRoot {
 
 /// KubernetesOptions represents additional Kubernetes-related options
 /// such as pod security policy, feature gates, etc.
-#[derive(Debug, Deserialize, Serialize)]
+///
+/// Use `KubernetesOptions::new` to build options for `CreateOpts`, struct literals
+/// have to set the `extra` field too. Its fields are sent along with the modelled ones.
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct KubernetesOptions {
     /// Flag that indicates if PodSecurityPolicy admission controller
     /// must be turned on or off.
     pub enable_pod_security_policy: bool,
+
+    /// Fields of the response that aren't modelled by this struct.
+    /// They are kept as is when the struct is serialized back.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl KubernetesOptions {
+    /// Construct the new KubernetesOptions without extra fields.
+    pub fn new(enable_pod_security_policy: bool) -> KubernetesOptions {
+        KubernetesOptions {
+            enable_pod_security_policy,
+            extra: serde_json::Map::new(),
+        }
+    }
 }
 
 /// Create options for a new cluster.
@@ -242,3 +400,85 @@ impl CreateOpts {
 pub struct CreateOptsRoot<'a> {
     pub cluster: &'a CreateOpts,
 }
+
+impl CheckSchema for Cluster {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck) {
+        check.extra(field, &self.extra);
+        check.extra(
+            &join(field, "kubernetes_options"),
+            &self.kubernetes_options.extra,
+        );
+        if let Status::Other(status) = &self.status {
+            check.unknown_value(&join(field, "status"), status);
+        }
+    }
+}
+
+impl CheckSchema for ClusterRoot {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck) {
+        self.cluster.check_schema(&join(field, "cluster"), check);
+    }
+}
+
+impl CheckSchema for ListRoot {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck) {
+        self.clusters.check_schema(&join(field, "clusters"), check);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_opts_with_kubernetes_options() {
+        let opts = CreateOpts::new("cluster", "1.17.9", "ru-1")
+            .with_kubernetes_options(KubernetesOptions::new(true));
+
+        let serialized = serde_json::to_value(&opts).unwrap();
+        assert_eq!(
+            serialized["kubernetes_options"],
+            serde_json::json!({"enable_pod_security_policy": true})
+        );
+    }
+
+    #[test]
+    fn status_helpers() {
+        let active = Status::Active;
+        assert_eq!(active.phase(), Phase::Active);
+        assert!(active.is_terminal());
+        assert!(active.accepts_mutations());
+
+        let resizing = Status::PendingResize;
+        assert_eq!(resizing.phase(), Phase::Pending);
+        assert!(resizing.is_pending());
+        assert!(!resizing.is_terminal());
+        assert!(!resizing.accepts_mutations());
+
+        assert!(Status::Error.is_error());
+        assert!(Status::Error.is_terminal());
+        assert!(!Status::Maintenance.accepts_mutations());
+
+        let hibernating = Status::Other(String::from("PENDING_HIBERNATE"));
+        assert_eq!(hibernating.phase(), Phase::Unknown);
+        assert!(!hibernating.is_terminal());
+    }
+
+    #[test]
+    fn parse_status() {
+        assert_eq!("ACTIVE".parse::<Status>().unwrap(), Status::Active);
+        assert_eq!(
+            "pending_upgrade_patch_version".parse::<Status>().unwrap(),
+            Status::PendingUpgradePatchVersion
+        );
+        assert!(matches!(
+            "actve".parse::<Status>(),
+            Err(Error::ValueError(_))
+        ));
+        assert_eq!(Status::from(String::from("ACTIVE")), Status::Active);
+
+        let mut counts = std::collections::HashMap::new();
+        *counts.entry(Status::Active).or_insert(0) += 1;
+        assert_eq!(counts[&Status::Active], 1);
+    }
+}
@@ -1,6 +1,24 @@
+use hyper::header::HeaderMap;
+use serde::Deserialize;
+
+use super::retry;
+use super::schema;
+
+/// Response header with the server request identifier.
+pub(crate) const REQUEST_ID_HEADER: &str = "x-request-id";
+
 /// MKS error return type.
 #[derive(Debug)]
 pub enum Error {
+    /// Failed to authenticate with Keystone.
+    AuthError(String),
+
+    /// Bad client configuration.
+    ConfigError(String),
+
+    /// Failed to configure HTTP connector.
+    ConnectorError(std::io::Error),
+
     /// Failed to deserialize response body.
     DeserializeError(serde_json::Error, String),
 
@@ -8,52 +26,337 @@ pub enum Error {
     SerializeError(serde_json::Error),
 
     /// Bad endpoint value.
-    EndpointError,
+    EndpointError(url::ParseError),
 
     /// Empty token value.
     EmptyTokenError,
 
     /// HTTP response contains bad status code.
-    HttpError(u16, String),
+    HttpError(ApiError),
 
     /// Failed to perform HTTP request with Hyper.
     HyperError(hyper::Error),
 
+    /// Request path isn't relative to the client endpoint.
+    PathError(String),
+
+    /// Bad proxy URL.
+    ProxyError(url::ParseError),
+
+    /// Unknown region name.
+    RegionError(String),
+
+    /// Response doesn't match the schema in strict mode.
+    SchemaError(Vec<schema::SchemaWarning>),
+
     /// Error while building a new request.
-    RequestError,
+    RequestError(hyper::header::InvalidHeaderValue),
+
+    /// Failed to start an async runtime for a blocking call.
+    RuntimeError(std::io::Error),
 
     /// Request timed out.
     TimeoutError,
 
+    /// Bad TLS configuration.
+    TlsError(Box<dyn std::error::Error + Send + Sync>),
+
+    /// Failed to get a token from the token provider.
+    TokenError(Box<dyn std::error::Error + Send + Sync>),
+
+    /// Failed to send request with a custom transport.
+    TransportError(Box<dyn std::error::Error + Send + Sync>),
+
+    /// Failed to plan Kubernetes version upgrade.
+    UpgradeError(String),
+
+    /// Failed to convert URL into URI for a new request.
+    UriError(hyper::http::uri::InvalidUri),
+
     /// Bad URL for a new request.
-    UrlError,
+    UrlError(url::ParseError),
+
+    /// Unknown cluster status, task status or task type.
+    ValueError(String),
+
+    /// Bad Kubernetes version.
+    VersionError(String),
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &*self {
+        match self {
+            Error::AuthError(err) => format!("Failed to authenticate, error: {}", err).fmt(f),
+            Error::ConfigError(err) => format!("Bad client configuration: {}", err).fmt(f),
+            Error::ConnectorError(err) => {
+                format!("Failed to configure HTTP connector, error: {}", err).fmt(f)
+            }
             Error::DeserializeError(err, body) => {
                 format!("Failed to deserialize body: {}, error: {}", err, body).fmt(f)
             }
             Error::SerializeError(err) => {
                 format!("Failed to serialize a struct, error: {}", err).fmt(f)
             }
-            Error::EndpointError => "Failed to parse base endpoint URL".fmt(f),
-            Error::EmptyTokenError => "Token cannot be empty".fmt(f),
-            Error::HttpError(status, err) => {
-                format!("Bad status code: {}, error body: {}", status, err).fmt(f)
+            Error::EndpointError(err) => {
+                format!("Failed to parse base endpoint URL, error: {}", err).fmt(f)
             }
+            Error::EmptyTokenError => "Token cannot be empty".fmt(f),
+            Error::HttpError(err) => err.fmt(f),
             Error::HyperError(err) => {
                 format!("Failed to make the request due to Hyper error: {}", err).fmt(f)
             }
-            Error::RequestError => "Failed to build a new request".fmt(f),
+            Error::PathError(path) => format!("Bad request path: {}", path).fmt(f),
+            Error::ProxyError(err) => format!("Failed to parse proxy URL, error: {}", err).fmt(f),
+            Error::RegionError(region) => format!("Unknown region: {}", region).fmt(f),
+            Error::SchemaError(warnings) => {
+                let warnings: Vec<String> = warnings.iter().map(|w| w.to_string()).collect();
+                format!("Response doesn't match the schema: {}", warnings.join(", ")).fmt(f)
+            }
+            Error::RequestError(err) => {
+                format!("Failed to build a new request, error: {}", err).fmt(f)
+            }
+            Error::RuntimeError(err) => format!("Failed to start async runtime: {}", err).fmt(f),
             Error::TimeoutError => "Request timed out".fmt(f),
-            Error::UrlError => "Failed to parse URL for request".fmt(f),
+            Error::TlsError(err) => format!("Failed to configure TLS, error: {}", err).fmt(f),
+            Error::TokenError(err) => format!("Failed to get token, error: {}", err).fmt(f),
+            Error::TransportError(err) => {
+                format!("Failed to send request with transport, error: {}", err).fmt(f)
+            }
+            Error::UpgradeError(err) => format!("Failed to plan upgrade: {}", err).fmt(f),
+            Error::UriError(err) => {
+                format!("Failed to convert URL into request URI, error: {}", err).fmt(f)
+            }
+            Error::UrlError(err) => {
+                format!("Failed to parse URL for request, error: {}", err).fmt(f)
+            }
+            Error::ValueError(value) => format!("Unknown value: {}", value).fmt(f),
+            Error::VersionError(version) => {
+                format!("Failed to parse Kubernetes version: {}", version).fmt(f)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ConnectorError(err) => Some(err),
+            Error::DeserializeError(err, _) => Some(err),
+            Error::SerializeError(err) => Some(err),
+            Error::EndpointError(err) => Some(err),
+            Error::HttpError(err) => Some(err),
+            Error::HyperError(err) => Some(err),
+            Error::ProxyError(err) => Some(err),
+            Error::RequestError(err) => Some(err),
+            Error::RuntimeError(err) => Some(err),
+            Error::TlsError(err) => Some(err.as_ref()),
+            Error::TokenError(err) => Some(err.as_ref()),
+            Error::TransportError(err) => Some(err.as_ref()),
+            Error::UriError(err) => Some(err),
+            Error::UrlError(err) => Some(err),
+            Error::AuthError(_)
+            | Error::ConfigError(_)
+            | Error::EmptyTokenError
+            | Error::PathError(_)
+            | Error::RegionError(_)
+            | Error::SchemaError(_)
+            | Error::TimeoutError
+            | Error::UpgradeError(_)
+            | Error::ValueError(_)
+            | Error::VersionError(_) => None,
+        }
+    }
+}
+
+impl Error {
+    /// Check if the failed request can succeed after a retry.
+    ///
+    /// Connection errors, timeouts, 429 and 5xx responses are retryable.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::TimeoutError => true,
+            Error::HyperError(err) => err.is_connect() || err.is_closed(),
+            Error::HttpError(err) => match hyper::StatusCode::from_u16(err.status) {
+                Ok(status) => retry::is_retryable_status(status),
+                Err(_) => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Check if the requested resource is not found.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Error::HttpError(err) => err.is_not_found(),
+            _ => false,
         }
     }
+
+    /// Check if the request timed out.
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Error::TimeoutError => true,
+            Error::HyperError(err) => err.is_timeout(),
+            _ => false,
+        }
+    }
+
+    /// Get HTTP status code of the failed response.
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            Error::HttpError(err) => Some(err.status),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of the MKS API error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ApiErrorKind {
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict,
+    QuotaExceeded,
+    TooManyRequests,
+    ServerError,
+    Other,
+}
+
+/// ApiError represents an error response of the MKS API.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiError {
+    /// HTTP status code of the response.
+    pub status: u16,
+
+    /// Error code from the response body if it's provided.
+    pub code: Option<String>,
+
+    /// Error message from the response body or the raw body if it can't be parsed.
+    pub message: String,
+
+    /// Server request identifier.
+    pub request_id: Option<String>,
+
+    /// Raw response body.
+    pub body: String,
 }
 
-impl std::error::Error for Error {}
+// ErrorRoot represents a root of a deserialized error body.
+#[derive(Deserialize)]
+struct ErrorRoot {
+    error: ErrorBody,
+}
+
+// ErrorBody represents a deserialized error body from an API response.
+#[derive(Deserialize)]
+struct ErrorBody {
+    id: Option<String>,
+    code: Option<serde_json::Value>,
+    message: Option<String>,
+}
+
+impl ApiError {
+    /// Construct the new ApiError from the response parts.
+    pub fn from_response(status: u16, headers: &HeaderMap, body: String) -> ApiError {
+        let parsed = serde_json::from_str::<ErrorRoot>(&body)
+            .ok()
+            .map(|r| r.error);
+
+        let code = parsed
+            .as_ref()
+            .and_then(|e| e.code.as_ref())
+            .map(|code| match code {
+                serde_json::Value::String(code) => code.clone(),
+                code => code.to_string(),
+            });
+        let message = parsed
+            .as_ref()
+            .and_then(|e| e.message.clone())
+            .unwrap_or_else(|| body.clone());
+        let request_id = headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from)
+            .or_else(|| parsed.and_then(|e| e.id));
+
+        ApiError {
+            status,
+            code,
+            message,
+            request_id,
+            body,
+        }
+    }
+
+    /// Get kind of the error.
+    ///
+    /// Conflicts are reported as `QuotaExceeded` only if the error code of the response
+    /// is a quota code, messages are never used to get the kind.
+    pub fn kind(&self) -> ApiErrorKind {
+        match self.status {
+            400 => ApiErrorKind::BadRequest,
+            401 => ApiErrorKind::Unauthorized,
+            403 => ApiErrorKind::Forbidden,
+            404 => ApiErrorKind::NotFound,
+            409 if self.has_quota_code() => ApiErrorKind::QuotaExceeded,
+            409 => ApiErrorKind::Conflict,
+            429 => ApiErrorKind::TooManyRequests,
+            500..=599 => ApiErrorKind::ServerError,
+            _ => ApiErrorKind::Other,
+        }
+    }
+
+    // Check if the error code of the response is a quota code, for example "QUOTA_EXCEEDED".
+    fn has_quota_code(&self) -> bool {
+        self.code
+            .as_deref()
+            .is_some_and(|code| code.to_ascii_lowercase().contains("quota"))
+    }
+
+    /// Check if the requested resource is not found.
+    pub fn is_not_found(&self) -> bool {
+        self.kind() == ApiErrorKind::NotFound
+    }
+
+    /// Check if the token is invalid or expired.
+    pub fn is_unauthorized(&self) -> bool {
+        self.kind() == ApiErrorKind::Unauthorized
+    }
+
+    /// Check if the token has no access to the requested resource.
+    pub fn is_forbidden(&self) -> bool {
+        self.kind() == ApiErrorKind::Forbidden
+    }
+
+    /// Check if the request conflicts with the current state of the resource.
+    pub fn is_conflict(&self) -> bool {
+        self.kind() == ApiErrorKind::Conflict
+    }
+
+    /// Check if the request exceeds project quotas.
+    pub fn is_quota_exceeded(&self) -> bool {
+        self.kind() == ApiErrorKind::QuotaExceeded
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Bad status code: {}", self.status)?;
+        if let Some(code) = &self.code {
+            write!(f, ", code: {}", code)?;
+        }
+        write!(f, ", error: {}", self.message)?;
+        if let Some(request_id) = &self.request_id {
+            write!(f, ", request ID: {}", request_id)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ApiError {}
 
 impl std::convert::From<hyper::Error> for Error {
     fn from(e: hyper::Error) -> Self {
@@ -84,8 +387,131 @@ mod tests {
 
         impl std::error::Error for B {}
 
-        let err = B(Some(Box::new(Error::RequestError)));
+        let err = B(Some(Box::new(Error::TimeoutError)));
+
+        let _err = &err as &dyn std::error::Error;
+        assert!(err.0.is_some());
+    }
+
+    #[test]
+    fn error_source() {
+        use std::error::Error as _;
+
+        let err = Error::EndpointError(url::Url::parse("not a url").unwrap_err());
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            url::ParseError::RelativeUrlWithoutBase.to_string()
+        );
+
+        let err = Error::RequestError(hyper::header::HeaderValue::from_str("\n").unwrap_err());
+        assert!(err.source().is_some());
+
+        let err = Error::HttpError(ApiError::from_response(
+            404,
+            &HeaderMap::new(),
+            String::new(),
+        ));
+        assert!(err.source().unwrap().is::<ApiError>());
+
+        assert!(Error::TimeoutError.source().is_none());
+    }
+
+    #[test]
+    fn error_classification() {
+        let not_found = Error::HttpError(ApiError::from_response(
+            404,
+            &HeaderMap::new(),
+            String::new(),
+        ));
+        assert!(not_found.is_not_found());
+        assert!(!not_found.is_retryable());
+        assert!(!not_found.is_timeout());
+        assert_eq!(not_found.status_code(), Some(404));
+
+        let unavailable = Error::HttpError(ApiError::from_response(
+            503,
+            &HeaderMap::new(),
+            String::new(),
+        ));
+        assert!(unavailable.is_retryable());
+        assert_eq!(unavailable.status_code(), Some(503));
+
+        let throttled = Error::HttpError(ApiError::from_response(
+            429,
+            &HeaderMap::new(),
+            String::new(),
+        ));
+        assert!(throttled.is_retryable());
+
+        assert!(Error::TimeoutError.is_retryable());
+        assert!(Error::TimeoutError.is_timeout());
+        assert_eq!(Error::TimeoutError.status_code(), None);
+        assert!(!Error::EmptyTokenError.is_retryable());
+    }
+
+    #[test]
+    fn api_error_from_json_body() {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_ID_HEADER, "req-1".parse().unwrap());
+        let body = r#"{"error": {"id": "id-1", "message": "cluster is not found"}}"#;
+
+        let err = ApiError::from_response(404, &headers, String::from(body));
+
+        assert_eq!(err.status, 404);
+        assert_eq!(err.code, None);
+        assert_eq!(err.message, "cluster is not found");
+        assert_eq!(err.request_id, Some(String::from("req-1")));
+        assert_eq!(err.body, body);
+        assert_eq!(err.kind(), ApiErrorKind::NotFound);
+        assert!(err.is_not_found());
+    }
+
+    #[test]
+    fn api_error_from_raw_body() {
+        let body = "upstream connect error";
+
+        let err = ApiError::from_response(503, &HeaderMap::new(), String::from(body));
+
+        assert_eq!(err.message, body);
+        assert_eq!(err.request_id, None);
+        assert_eq!(err.kind(), ApiErrorKind::ServerError);
+    }
+
+    #[test]
+    fn api_error_kinds() {
+        let body = r#"{"error": {"id": "id-2", "code": 42, "message": "bad value"}}"#;
+        let err = ApiError::from_response(400, &HeaderMap::new(), String::from(body));
+        assert_eq!(err.code, Some(String::from("42")));
+        assert_eq!(err.request_id, Some(String::from("id-2")));
+        assert_eq!(err.kind(), ApiErrorKind::BadRequest);
+
+        let body = r#"{"error": {"code": "QUOTA_EXCEEDED", "message": "not enough"}}"#;
+        let err = ApiError::from_response(409, &HeaderMap::new(), String::from(body));
+        assert!(err.is_quota_exceeded());
+        assert!(!err.is_conflict());
+
+        let err = ApiError::from_response(409, &HeaderMap::new(), String::new());
+        assert!(err.is_conflict());
+
+        let err = ApiError::from_response(401, &HeaderMap::new(), String::new());
+        assert!(err.is_unauthorized());
+
+        let err = ApiError::from_response(403, &HeaderMap::new(), String::new());
+        assert!(err.is_forbidden());
+    }
+
+    #[test]
+    fn api_error_kinds_ignore_quota_messages() {
+        let body = r#"{"error": {"message": "quota of cluster is not found"}}"#;
+        let err = ApiError::from_response(404, &HeaderMap::new(), String::from(body));
+        assert!(err.is_not_found());
+        assert!(!err.is_quota_exceeded());
+
+        let err = ApiError::from_response(409, &HeaderMap::new(), String::from(body));
+        assert!(err.is_conflict());
 
-        let _err = &err as &(dyn std::error::Error);
+        let body = r#"{"error": {"code": "QUOTA_EXCEEDED", "message": "not enough"}}"#;
+        let err = ApiError::from_response(403, &HeaderMap::new(), String::from(body));
+        assert!(err.is_forbidden());
     }
 }
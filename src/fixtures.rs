@@ -0,0 +1,26 @@
+// Response bodies that are shared by unit tests of several modules.
+
+// Cluster from the MKS API response.
+pub(crate) const CLUSTER: &str = r#"{
+    "id": "dbe7559b-55d8-4f65-9230-6a22b985ff73",
+    "created_at": "2020-02-13T09:18:32.05753Z",
+    "updated_at": "2020-02-13T09:35:04.893559Z",
+    "name": "test-cluster",
+    "status": "ACTIVE",
+    "project_id": "65044a03bcb24b4d8a6a3c9df96fd0e8",
+    "network_id": "74a591a4-5c98-4a0c-a8e9-bd1e4f48ec38",
+    "subnet_id": "c872d0bb-c1c8-4ad2-a0c9-5cae8f52c2a8",
+    "kube_api_ip": "203.0.113.101",
+    "kube_version": "1.17.9",
+    "region": "ru-1",
+    "pki_tree_updated_at": null,
+    "maintenance_window_start": "01:00:00",
+    "maintenance_window_end": "03:00:00",
+    "maintenance_last_start": "2020-02-13T01:00:00Z",
+    "enable_autorepair": true,
+    "enable_patch_version_auto_upgrade": false,
+    "zonal": false,
+    "kubernetes_options": {
+        "enable_pod_security_policy": true
+    }
+}"#;
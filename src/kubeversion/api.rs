@@ -1,17 +1,20 @@
 use hyper::Method;
 
 use super::super::error::Error;
-use super::super::resource_url::{API_VERSION, KUBEVERSIONS};
+use super::super::resource_url::{expand, KUBEVERSIONS_PATH};
+use super::super::response::Response;
 use super::super::Client;
 use super::schemas;
 
-pub fn list(client: &Client) -> Result<Vec<schemas::KubeVersion>, Error> {
-    let path = format!("/{}/{}", API_VERSION, KUBEVERSIONS);
-    let req = client.new_request(Method::GET, &path, None)?;
-    let body = client.do_request(req)?;
+api_call! {
+    name = "mks.kubeversion.list",
+    skip(client),
+    pub async fn list(client: &Client) -> Result<Response<Vec<schemas::KubeVersion>>, Error> {
+        let path = expand(KUBEVERSIONS_PATH, &[])?;
+        let req = client.new_request(Method::GET, &path, None)?;
+        let deserialized: Response<schemas::KubeVersionsRoot> =
+            client.do_request_json(req, KUBEVERSIONS_PATH).await?;
 
-    let deserialized: schemas::KubeVersionsRoot =
-        serde_json::from_str(&body).map_err(|err| Error::DeserializeError(err, body))?;
-
-    Ok(deserialized.kube_versions)
+        Ok(deserialized.map(|root| root.kube_versions))
+    }
 }
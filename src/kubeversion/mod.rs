@@ -1,2 +1,3 @@
 pub(crate) mod api;
 pub mod schemas;
+pub mod upgrade;
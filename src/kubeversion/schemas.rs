@@ -1,4 +1,9 @@
 use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use super::super::error::Error;
+use super::super::schema::{CheckSchema, SchemaCheck};
 
 /// KubeVersion represents a deserialized Kubernetes version body from an API response.
 #[derive(Debug, Deserialize, Serialize)]
@@ -10,8 +15,208 @@ pub struct KubeVersion {
     pub is_default: bool,
 }
 
+impl KubeVersion {
+    /// Parse the version.
+    pub fn semver(&self) -> Result<KubeSemver, Error> {
+        self.version.parse()
+    }
+}
+
+/// Helpers to select versions from the `list_kube_versions` result.
+pub trait KubeVersions {
+    /// Get the default version.
+    fn default_version(&self) -> Option<&KubeVersion>;
+
+    /// Get the newest version.
+    fn latest(&self) -> Option<&KubeVersion>;
+
+    /// Get the newest patch version with the same major and minor versions.
+    fn latest_patch_for(&self, version: &KubeSemver) -> Option<&KubeVersion>;
+
+    /// Check if the version is in the list.
+    fn is_supported(&self, version: &KubeSemver) -> bool;
+}
+
+// Versions that can't be parsed are skipped by the helpers.
+impl KubeVersions for [KubeVersion] {
+    fn default_version(&self) -> Option<&KubeVersion> {
+        self.iter().find(|v| v.is_default)
+    }
+
+    fn latest(&self) -> Option<&KubeVersion> {
+        self.iter()
+            .filter_map(|v| v.semver().ok().map(|semver| (semver, v)))
+            .max_by_key(|(semver, _)| *semver)
+            .map(|(_, v)| v)
+    }
+
+    fn latest_patch_for(&self, version: &KubeSemver) -> Option<&KubeVersion> {
+        self.iter()
+            .filter_map(|v| v.semver().ok().map(|semver| (semver, v)))
+            .filter(|(semver, _)| semver.same_minor(version))
+            .max_by_key(|(semver, _)| *semver)
+            .map(|(_, v)| v)
+    }
+
+    fn is_supported(&self, version: &KubeSemver) -> bool {
+        self.iter()
+            .any(|v| v.semver().ok().as_ref() == Some(version))
+    }
+}
+
+/// KubeSemver represents a Kubernetes version in format: "X.Y.Z".
+///
+/// Versions are ordered by their numeric components, so "1.9.0" < "1.17.0".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KubeSemver {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+impl KubeSemver {
+    /// Construct the new KubeSemver.
+    pub fn new(major: u64, minor: u64, patch: u64) -> KubeSemver {
+        KubeSemver {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Get major version.
+    pub fn major(&self) -> u64 {
+        self.major
+    }
+
+    /// Get minor version.
+    pub fn minor(&self) -> u64 {
+        self.minor
+    }
+
+    /// Get patch version.
+    pub fn patch(&self) -> u64 {
+        self.patch
+    }
+
+    /// Check if both versions have the same major and minor versions.
+    pub fn same_minor(&self, other: &KubeSemver) -> bool {
+        self.major == other.major && self.minor == other.minor
+    }
+}
+
+impl std::fmt::Display for KubeSemver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for KubeSemver {
+    type Err = Error;
+
+    /// Parse version in format "X.Y.Z", an optional "v" prefix is allowed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let version = s.trim();
+        let version = version.strip_prefix('v').unwrap_or(version);
+        let mut parts = version.split('.').map(|part| part.parse::<u64>());
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => {
+                Ok(KubeSemver::new(major, minor, patch))
+            }
+            _ => Err(Error::VersionError(String::from(s))),
+        }
+    }
+}
+
+impl TryFrom<String> for KubeSemver {
+    type Error = Error;
+
+    fn try_from(version: String) -> Result<Self, Self::Error> {
+        version.parse()
+    }
+}
+
+impl From<KubeSemver> for String {
+    fn from(version: KubeSemver) -> Self {
+        version.to_string()
+    }
+}
+
 /// KubeVersionsRoot represents a list of deserialized Kubernetes versions.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct KubeVersionsRoot {
     pub kube_versions: Vec<KubeVersion>,
 }
+
+// Kubernetes versions don't have enum fields or flattened maps.
+impl CheckSchema for KubeVersionsRoot {
+    fn check_schema(&self, _field: &str, _check: &mut SchemaCheck) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kube_versions(versions: &[&str], default: &str) -> Vec<KubeVersion> {
+        versions
+            .iter()
+            .map(|version| KubeVersion {
+                version: String::from(*version),
+                is_default: *version == default,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_kube_semver() {
+        let version: KubeSemver = "1.17.9".parse().unwrap();
+        assert_eq!(version, KubeSemver::new(1, 17, 9));
+        assert_eq!(version.minor(), 17);
+        assert_eq!(version.patch(), 9);
+        assert_eq!(version.to_string(), "1.17.9");
+        assert_eq!(
+            "v1.9.0".parse::<KubeSemver>().unwrap(),
+            KubeSemver::new(1, 9, 0)
+        );
+
+        for bad in &["1.17", "1.17.9.1", "1.x.9", ""] {
+            assert!(matches!(
+                bad.parse::<KubeSemver>(),
+                Err(Error::VersionError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn order_kube_semver() {
+        let mut versions: Vec<KubeSemver> = ["1.17.9", "1.9.11", "1.17.10", "1.16.3"]
+            .iter()
+            .map(|v| v.parse().unwrap())
+            .collect();
+        versions.sort();
+
+        let versions: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        assert_eq!(versions, vec!["1.9.11", "1.16.3", "1.17.9", "1.17.10"]);
+    }
+
+    #[test]
+    fn select_kube_versions() {
+        let versions = kube_versions(&["1.9.11", "1.17.9", "1.17.10", "1.16.3", "bad"], "1.16.3");
+
+        assert_eq!(versions.default_version().unwrap().version, "1.16.3");
+        assert_eq!(versions.latest().unwrap().version, "1.17.10");
+        assert_eq!(
+            versions
+                .latest_patch_for(&KubeSemver::new(1, 17, 0))
+                .unwrap()
+                .version,
+            "1.17.10"
+        );
+        assert!(versions
+            .latest_patch_for(&KubeSemver::new(1, 18, 0))
+            .is_none());
+        assert!(versions.is_supported(&KubeSemver::new(1, 9, 11)));
+        assert!(!versions.is_supported(&KubeSemver::new(1, 9, 10)));
+    }
+}
@@ -0,0 +1,222 @@
+use super::super::cluster::schemas::Status;
+use super::super::error::Error;
+use super::super::task::schemas::Type;
+use super::schemas::{KubeSemver, KubeVersion, KubeVersions};
+
+/// UpgradeKind represents kinds of Kubernetes version upgrades.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UpgradeKind {
+    /// Upgrade to a newer patch version of the same minor version.
+    Patch,
+
+    /// Upgrade to the next minor version.
+    Minor,
+}
+
+impl UpgradeKind {
+    /// Get cluster status while the upgrade is in progress.
+    pub fn cluster_status(&self) -> Status {
+        match self {
+            UpgradeKind::Patch => Status::PendingUpgradePatchVersion,
+            UpgradeKind::Minor => Status::PendingUpgradeMinorVersion,
+        }
+    }
+
+    /// Get type of the upgrade task.
+    pub fn task_type(&self) -> Type {
+        match self {
+            UpgradeKind::Patch => Type::UpgradePatchVersion,
+            UpgradeKind::Minor => Type::UpgradeMinorVersion,
+        }
+    }
+}
+
+/// UpgradeStep represents a single upgrade of the cluster Kubernetes version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UpgradeStep {
+    /// Version before the upgrade.
+    pub from: KubeSemver,
+
+    /// Version after the upgrade.
+    pub to: KubeSemver,
+
+    /// Kind of the upgrade.
+    pub kind: UpgradeKind,
+}
+
+/// UpgradePlan represents an ordered sequence of upgrades to the target Kubernetes version.
+///
+/// Kubernetes can be upgraded by one minor version at a time, so every minor
+/// upgrade is preceded by an upgrade to the latest patch of the current minor version.
+/// Intermediate minor versions are upgraded to their latest patch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpgradePlan {
+    /// Upgrade steps in the order they must be applied.
+    pub steps: Vec<UpgradeStep>,
+}
+
+impl UpgradePlan {
+    /// Plan upgrade from the current version to the target one.
+    ///
+    /// Use the `list_kube_versions` result as supported versions and `Cluster::kube_semver`
+    /// as the current version. The target version must be supported.
+    pub fn new(
+        kube_versions: &[KubeVersion],
+        current: &KubeSemver,
+        target: &KubeSemver,
+    ) -> Result<UpgradePlan, Error> {
+        if !kube_versions.is_supported(target) {
+            return Err(Error::UpgradeError(format!(
+                "version {} is not supported",
+                target
+            )));
+        }
+        if target < current {
+            return Err(Error::UpgradeError(format!(
+                "version {} can't be downgraded to {}",
+                current, target
+            )));
+        }
+        if target.major() != current.major() {
+            return Err(Error::UpgradeError(format!(
+                "major version {} can't be upgraded to {}",
+                current, target
+            )));
+        }
+
+        let mut steps = Vec::new();
+        let mut version = *current;
+        while !version.same_minor(target) {
+            // Upgrade to the latest patch before the next minor version.
+            if let Some(latest) = latest_patch_for(kube_versions, &version) {
+                if latest > version {
+                    steps.push(UpgradeStep {
+                        from: version,
+                        to: latest,
+                        kind: UpgradeKind::Patch,
+                    });
+                    version = latest;
+                }
+            }
+
+            let next_minor = KubeSemver::new(version.major(), version.minor() + 1, 0);
+            let next = if next_minor.same_minor(target) {
+                *target
+            } else {
+                latest_patch_for(kube_versions, &next_minor).ok_or_else(|| {
+                    Error::UpgradeError(format!(
+                        "no supported versions for {}.{}",
+                        next_minor.major(),
+                        next_minor.minor()
+                    ))
+                })?
+            };
+            steps.push(UpgradeStep {
+                from: version,
+                to: next,
+                kind: UpgradeKind::Minor,
+            });
+            version = next;
+        }
+
+        if version < *target {
+            steps.push(UpgradeStep {
+                from: version,
+                to: *target,
+                kind: UpgradeKind::Patch,
+            });
+        }
+
+        Ok(UpgradePlan { steps })
+    }
+
+    /// Check if the cluster already has the target version.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+fn latest_patch_for(kube_versions: &[KubeVersion], version: &KubeSemver) -> Option<KubeSemver> {
+    kube_versions
+        .latest_patch_for(version)
+        .and_then(|v| v.semver().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kube_versions() -> Vec<KubeVersion> {
+        [
+            "1.15.12", "1.16.8", "1.16.15", "1.17.9", "1.17.12", "1.18.6",
+        ]
+        .iter()
+        .map(|version| KubeVersion {
+            version: String::from(*version),
+            is_default: false,
+        })
+        .collect()
+    }
+
+    fn semver(version: &str) -> KubeSemver {
+        version.parse().unwrap()
+    }
+
+    fn step(from: &str, to: &str, kind: UpgradeKind) -> UpgradeStep {
+        UpgradeStep {
+            from: semver(from),
+            to: semver(to),
+            kind,
+        }
+    }
+
+    #[test]
+    fn plan_minor_upgrades() {
+        let plan =
+            UpgradePlan::new(&kube_versions(), &semver("1.15.3"), &semver("1.17.9")).unwrap();
+
+        assert_eq!(
+            plan.steps,
+            vec![
+                step("1.15.3", "1.15.12", UpgradeKind::Patch),
+                step("1.15.12", "1.16.15", UpgradeKind::Minor),
+                step("1.16.15", "1.17.9", UpgradeKind::Minor),
+            ]
+        );
+        assert_eq!(
+            plan.steps[0].kind.cluster_status(),
+            Status::PendingUpgradePatchVersion
+        );
+        assert_eq!(plan.steps[1].kind.task_type(), Type::UpgradeMinorVersion);
+    }
+
+    #[test]
+    fn plan_patch_upgrade() {
+        let versions = kube_versions();
+
+        let plan = UpgradePlan::new(&versions, &semver("1.17.9"), &semver("1.17.12")).unwrap();
+        assert_eq!(
+            plan.steps,
+            vec![step("1.17.9", "1.17.12", UpgradeKind::Patch)]
+        );
+
+        let plan = UpgradePlan::new(&versions, &semver("1.18.6"), &semver("1.18.6")).unwrap();
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn bad_upgrade_plans() {
+        let versions = kube_versions();
+
+        for (current, target) in &[
+            ("1.17.9", "1.19.0"),
+            ("1.17.9", "1.16.15"),
+            ("1.13.1", "1.15.12"),
+        ] {
+            assert!(matches!(
+                UpgradePlan::new(&versions, &semver(current), &semver(target)),
+                Err(Error::UpgradeError(_))
+            ));
+        }
+    }
+}
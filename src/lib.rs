@@ -1,21 +1,76 @@
-use error::Error;
-use std::time::Duration;
-use tokio::time::timeout;
+use auth::keystone::KeystoneAuth;
+use error::{ApiError, Error};
+use metrics::{MetricsRecorder, RequestMetrics};
+use middleware::{Middleware, Next};
+use multi_region::MultiRegionClient;
+use proxy::{Proxy, ProxyTransport};
+use region::Region;
+use response::{MetadataClient, Response};
+use retry::RetryPolicy;
+use schema::{CheckSchema, SchemaReporter};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tls::{Certificate, Identity, TlsConfig};
+use token::{StaticToken, TokenProvider};
+use tokio::time::{delay_for, timeout};
+use tracing::Span;
+use transport::{HyperTransport, Transport};
 use url::Url;
 
 // Hyper imports.
-use hyper::body::Buf;
+use hyper::body::{Buf, Bytes};
 use hyper::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, USER_AGENT};
-use hyper::{Method, Request};
+use hyper::{Method, Request, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 #[cfg(feature = "rustls")]
 type HttpsConnector = hyper_rustls::HttpsConnector<hyper::client::HttpConnector>;
 #[cfg(feature = "rust-native-tls")]
-use hyper_tls;
-#[cfg(feature = "rust-native-tls")]
 type HttpsConnector = hyper_tls::HttpsConnector<hyper::client::HttpConnector>;
 
+// Declare an API call with a tracing span.
+//
+// Arguments that aren't skipped are recorded as span fields. Method, path template,
+// status code, latency and retries count are recorded by `Client::do_request`,
+// or by the call itself if it doesn't use the client.
+macro_rules! api_call {
+    (name = $name:literal, skip($($skip:ident),+), $call:item) => {
+        #[tracing::instrument(
+            name = $name,
+            skip($($skip),+),
+            fields(
+                method = tracing::field::Empty,
+                path = tracing::field::Empty,
+                status = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+                retries = tracing::field::Empty,
+            )
+        )]
+        $call
+    };
+}
+
+pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod config;
 pub mod error;
+#[cfg(test)]
+mod fixtures;
+pub mod metrics;
+pub mod middleware;
+pub mod multi_region;
+pub mod proxy;
+pub(crate) mod raw;
+pub mod region;
 pub mod resource_url;
+pub mod response;
+pub mod retry;
+pub mod schema;
+pub mod tls;
+pub mod token;
+pub mod transport;
 
 pub mod cluster;
 pub mod kubeversion;
@@ -23,17 +78,28 @@ pub mod node;
 pub mod nodegroup;
 pub mod task;
 
+// Header with the authentication token.
+const AUTH_TOKEN_HEADER: &str = "x-auth-token";
+
 // Environment variables from Cargo.
 static PKG_NAME: &str = env!("CARGO_PKG_NAME");
 static PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// `Client` struct is used to make calls to the MKS API.
+///
+/// All API methods are asynchronous and run on the caller's Tokio runtime.
+/// Enable the `blocking` feature and use `blocking::Client` for synchronous calls.
 pub struct Client {
-    client: hyper::Client<HttpsConnector>,
-    token: String,
+    transport: Arc<dyn Transport>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    metrics: Option<Arc<dyn MetricsRecorder>>,
+    token_provider: Arc<dyn TokenProvider>,
     base_endpoint: url::Url,
     user_agent: String,
     timeout: Duration,
+    retry_policy: RetryPolicy,
+    strict_schemas: bool,
+    schema_reporter: Option<Arc<dyn SchemaReporter>>,
 }
 
 impl Client {
@@ -41,38 +107,40 @@ impl Client {
     ///
     /// Use `Builder` to configure the client.
     pub fn new(base_endpoint: &str, token: &str) -> Result<Client, Error> {
-        Client::with_builder(base_endpoint, token, Client::builder())
+        Client::builder().build(base_endpoint, token)
     }
 
-    fn with_builder(base_endpoint: &str, token: &str, builder: Builder) -> Result<Client, Error> {
-        // Check token.
-        if token.is_empty() {
-            return Err(Error::EmptyTokenError);
-        }
-        let token = String::from(token);
+    /// Construct the new Client struct for the region with default configuration.
+    pub fn for_region(region: Region, token: &str) -> Result<Client, Error> {
+        Client::builder().build_for_region(region, token)
+    }
 
+    fn with_builder(
+        base_endpoint: &str,
+        token_provider: Arc<dyn TokenProvider>,
+        builder: Builder,
+    ) -> Result<Client, Error> {
         // Check base endpoint.
-        let base_endpoint = Url::parse(base_endpoint).map_err(|_| Error::EndpointError)?;
-
-        // Use the provided Hyper client or configure a new one.
-        let client = match builder.client {
-            Some(client) => client,
-            None => {
-                #[cfg(feature = "rustls")]
-                let client = hyper::Client::builder().build(HttpsConnector::new());
-                #[cfg(feature = "rust-native-tls")]
-                let client = hyper::Client::builder().build(HttpsConnector::new()?);
-
-                client
-            }
-        };
+        // Its path must end with a slash so it's preserved when request paths are joined.
+        let mut base_endpoint = Url::parse(base_endpoint).map_err(Error::EndpointError)?;
+        if !base_endpoint.path().ends_with('/') {
+            let path = format!("{}/", base_endpoint.path());
+            base_endpoint.set_path(&path);
+        }
+
+        let transport = builder.make_transport()?;
 
         Ok(Client {
-            client,
-            token,
+            transport,
+            middlewares: builder.middlewares,
+            metrics: builder.metrics,
+            token_provider,
             base_endpoint,
             user_agent: Client::user_agent(),
             timeout: builder.timeout,
+            retry_policy: builder.retry_policy,
+            strict_schemas: builder.strict_schemas,
+            schema_reporter: builder.schema_reporter,
         })
     }
 
@@ -85,6 +153,11 @@ impl Client {
         Builder::default()
     }
 
+    /// Get client that returns responses with the HTTP status, headers and request ID.
+    pub fn with_metadata(&self) -> MetadataClient<'_> {
+        MetadataClient::new(self)
+    }
+
     // Prepare a new request.
     fn new_request(
         &self,
@@ -103,26 +176,20 @@ impl Client {
         // Add user-agent header.
         req.headers_mut().insert(
             USER_AGENT,
-            HeaderValue::from_str(&self.user_agent).map_err(|_| Error::RequestError)?,
-        );
-
-        // Add x-auth-token header.
-        req.headers_mut().insert(
-            "x-auth-token",
-            HeaderValue::from_str(&self.token).map_err(|_| Error::RequestError)?,
+            HeaderValue::from_str(&self.user_agent).map_err(Error::RequestError)?,
         );
 
         // Add body into the new request if it's provided.
         if let Some(body) = body {
             // Add content-length header if body is provided.
             let len =
-                HeaderValue::from_str(&body.len().to_string()).map_err(|_| Error::RequestError)?;
+                HeaderValue::from_str(&body.len().to_string()).map_err(Error::RequestError)?;
             req.headers_mut().insert(CONTENT_LENGTH, len);
 
             // Add content-type header if body is provided.
             req.headers_mut().insert(
                 CONTENT_TYPE,
-                HeaderValue::from_str("application/json").map_err(|_| Error::RequestError)?,
+                HeaderValue::from_str("application/json").map_err(Error::RequestError)?,
             );
 
             *req.body_mut() = hyper::Body::from(body);
@@ -131,174 +198,420 @@ impl Client {
         Ok(req)
     }
 
-    #[tokio::main]
-    async fn do_request(&self, req: hyper::Request<hyper::Body>) -> Result<String, Error> {
-        let duration = self.timeout;
-        let handle = async {
-            let raw_resp = self.client.request(req).await?;
+    // Perform a prepared request on the caller's runtime.
+    //
+    // Token is added to each attempt, a rejected token is refreshed once.
+    // Failed attempts are retried according to the client retry policy.
+    // Endpoint is a path template of the request that's used in spans and metrics.
+    // Status code, latency and retries count are recorded into the current span.
+    async fn do_request(
+        &self,
+        req: hyper::Request<hyper::Body>,
+        endpoint: &'static str,
+    ) -> Result<Response<Bytes>, Error> {
+        let started = Instant::now();
+        let span = Span::current();
+        span.record("method", req.method().as_str());
+        span.record("path", endpoint);
 
-            let status = raw_resp.status();
-            let body = hyper::body::aggregate(raw_resp).await?.to_bytes();
-            let body = String::from_utf8_lossy(&body);
+        let method = req.method().clone();
+        let mut retries = 0;
 
-            Ok::<_, hyper::Error>((body.to_string(), status))
-        };
+        // Attempts are made in a separate block so metrics are recorded for every failure.
+        let resp = async {
+            let (parts, body) = req.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            let retryable = self.retry_policy.allows(&parts.method, &parts.headers);
+
+            let mut token = self.token_provider.token().await?;
+            let mut token_refreshed = false;
+            loop {
+                // Hyper body can't be reused so the request is rebuilt for each attempt.
+                let mut req = Request::new(hyper::Body::from(body.clone()));
+                *req.method_mut() = parts.method.clone();
+                *req.uri_mut() = parts.uri.clone();
+                *req.headers_mut() = parts.headers.clone();
+
+                // Add x-auth-token header.
+                let mut token_value = HeaderValue::from_str(&token).map_err(Error::RequestError)?;
+                token_value.set_sensitive(true);
+                req.headers_mut().insert(AUTH_TOKEN_HEADER, token_value);
 
-        let raw_resp = timeout(duration, handle).await??;
+                let resp = self.send_request(req).await;
 
-        let (body, status) = raw_resp;
+                // Request with a rejected token isn't processed so it's safe to repeat it.
+                if let Ok((StatusCode::UNAUTHORIZED, _, _)) = &resp {
+                    if !token_refreshed {
+                        token_refreshed = true;
+                        // The same token would be rejected again.
+                        let refreshed = self.token_provider.refresh(&token).await?;
+                        if let Some(refreshed) = refreshed.filter(|refreshed| *refreshed != token) {
+                            token = refreshed;
+                            tracing::debug!("retrying request with refreshed token");
+                            continue;
+                        }
+                    }
+                }
 
+                // Retry the request only after an error that can be fixed by retrying.
+                let retry_delay = match &resp {
+                    Ok((status, headers, _)) if retry::is_retryable_status(*status) => {
+                        Some(retry::retry_after(headers))
+                    }
+                    Err(err) if err.is_retryable() => Some(None),
+                    _ => None,
+                };
+                if let Some(delay) = retry_delay {
+                    if retryable && retries < self.retry_policy.max_retries() {
+                        let delay = self.retry_policy.delay(retries, delay);
+                        retries += 1;
+                        tracing::debug!(
+                            retry = retries,
+                            delay_ms = delay.as_millis() as u64,
+                            "retrying failed request"
+                        );
+                        delay_for(delay).await;
+                        continue;
+                    }
+                }
+
+                return resp;
+            }
+        }
+        .await;
+
+        let status = resp.as_ref().ok().map(|(status, _, _)| status.as_u16());
+        let latency = started.elapsed();
+        span.record("retries", retries);
+        span.record("latency_ms", latency.as_millis() as u64);
+        if let Some(status) = status {
+            span.record("status", status);
+        }
+        if let Some(metrics) = &self.metrics {
+            metrics.record(&RequestMetrics {
+                method: &method,
+                endpoint,
+                status,
+                latency,
+                retries,
+                timed_out: resp.as_ref().err().is_some_and(Error::is_timeout),
+            });
+        }
+
+        let (status, headers, body) = resp?;
         if !status.is_success() {
-            return Err(Error::HttpError(status.as_u16(), body));
+            return Err(Error::HttpError(ApiError::from_response(
+                status.as_u16(),
+                &headers,
+                String::from_utf8_lossy(&body).into_owned(),
+            )));
+        }
+
+        Ok(Response::new(status, headers, body))
+    }
+
+    // Perform a prepared request and deserialize the JSON response body.
+    //
+    // Response is checked against the schema in strict mode. Warnings are passed
+    // to the schema reporter if it's set, otherwise they are returned as an error.
+    async fn do_request_json<R>(
+        &self,
+        req: hyper::Request<hyper::Body>,
+        endpoint: &'static str,
+    ) -> Result<Response<R>, Error>
+    where
+        R: DeserializeOwned + Serialize + CheckSchema,
+    {
+        let resp = self.do_request(req, endpoint).await?;
+        if !self.strict_schemas && self.schema_reporter.is_none() {
+            return resp.json();
         }
 
-        Ok(body)
+        let raw = resp.body().clone();
+        let resp = resp.json()?;
+        let warnings = schema::check(endpoint, &raw, resp.body())?;
+        if !warnings.is_empty() {
+            match &self.schema_reporter {
+                Some(reporter) => reporter.report(&warnings),
+                None => return Err(Error::SchemaError(warnings)),
+            }
+        }
+
+        Ok(resp)
+    }
+
+    // Send a single request attempt and read the whole response body.
+    async fn send_request(
+        &self,
+        req: hyper::Request<hyper::Body>,
+    ) -> Result<(hyper::StatusCode, hyper::HeaderMap, Bytes), Error> {
+        let duration = self.timeout;
+        let handle = async {
+            let raw_resp = Next::new(self.transport.as_ref(), &self.middlewares)
+                .run(req)
+                .await?;
+
+            let status = raw_resp.status();
+            let headers = raw_resp.headers().clone();
+            let body = hyper::body::aggregate(raw_resp).await?.to_bytes();
+
+            Ok::<_, Error>((status, headers, body))
+        };
+
+        timeout(duration, handle).await?
     }
 
+    // Build request URI relative to the base endpoint path.
     fn make_uri(&self, path: &str) -> Result<hyper::Uri, Error> {
+        // Absolute URLs can replace the endpoint host and leak the auth token.
+        if path.starts_with("//") || Url::parse(path).is_ok() {
+            return Err(Error::PathError(String::from(path)));
+        }
+
         let url = self
             .base_endpoint
-            .clone()
-            .join(path)
-            .map_err(|_| Error::UrlError)?;
+            .join(path.trim_start_matches('/'))
+            .map_err(Error::UrlError)?;
+        if url.scheme() != self.base_endpoint.scheme()
+            || url.host() != self.base_endpoint.host()
+            || url.port_or_known_default() != self.base_endpoint.port_or_known_default()
+        {
+            return Err(Error::PathError(String::from(path)));
+        }
 
-        url.as_str()
-            .parse::<hyper::Uri>()
-            .map_err(|_| Error::UrlError)
+        url.as_str().parse::<hyper::Uri>().map_err(Error::UriError)
     }
 }
 
 /// Methods to work with clusters.
 impl Client {
     /// Get a cluster.
-    pub fn get_cluster(&self, cluster_id: &str) -> Result<cluster::schemas::Cluster, Error> {
+    pub async fn get_cluster(&self, cluster_id: &str) -> Result<cluster::schemas::Cluster, Error> {
         cluster::api::get(self, cluster_id)
+            .await
+            .map(Response::into_body)
     }
 
     /// List clusters.
-    pub fn list_clusters(&self) -> Result<Vec<cluster::schemas::Cluster>, Error> {
-        cluster::api::list(self)
+    pub async fn list_clusters(&self) -> Result<Vec<cluster::schemas::Cluster>, Error> {
+        cluster::api::list(self).await.map(Response::into_body)
     }
 
     /// Create a cluster.
-    pub fn create_cluster(
+    pub async fn create_cluster(
         &self,
         opts: &cluster::schemas::CreateOpts,
     ) -> Result<cluster::schemas::Cluster, Error> {
         cluster::api::create(self, opts)
+            .await
+            .map(Response::into_body)
     }
 
     /// Delete a cluster.
-    pub fn delete_cluster(&self, cluster_id: &str) -> Result<(), Error> {
+    pub async fn delete_cluster(&self, cluster_id: &str) -> Result<(), Error> {
         cluster::api::delete(self, cluster_id)
+            .await
+            .map(Response::into_body)
     }
 }
 
 /// Methods to work with Kubernetes versions.
 impl Client {
     /// List all Kubernetes versions.
-    pub fn list_kube_versions(&self) -> Result<Vec<kubeversion::schemas::KubeVersion>, Error> {
-        kubeversion::api::list(self)
+    pub async fn list_kube_versions(
+        &self,
+    ) -> Result<Vec<kubeversion::schemas::KubeVersion>, Error> {
+        kubeversion::api::list(self).await.map(Response::into_body)
     }
 }
 
 /// Methods to work with nodes.
 impl Client {
     /// Get a cluster node.
-    pub fn get_node(
+    pub async fn get_node(
         &self,
         cluster_id: &str,
         nodegroup_id: &str,
         node_id: &str,
     ) -> Result<node::schemas::Node, Error> {
         node::api::get(self, cluster_id, nodegroup_id, node_id)
+            .await
+            .map(Response::into_body)
     }
 
     /// Reinstall a cluster node.
-    pub fn reinstall_node(
+    pub async fn reinstall_node(
         &self,
         cluster_id: &str,
         nodegroup_id: &str,
         node_id: &str,
     ) -> Result<(), Error> {
         node::api::reinstall(self, cluster_id, nodegroup_id, node_id)
+            .await
+            .map(Response::into_body)
     }
 }
 
 /// Methods to work with nodegroups.
 impl Client {
     /// Get a cluster nodegroup.
-    pub fn get_nodegroup(
+    pub async fn get_nodegroup(
         &self,
         cluster_id: &str,
         nodegroup_id: &str,
     ) -> Result<nodegroup::schemas::Nodegroup, Error> {
         nodegroup::api::get(self, cluster_id, nodegroup_id)
+            .await
+            .map(Response::into_body)
     }
 
     /// List cluster nodegroups.
-    pub fn list_nodegroups(
+    pub async fn list_nodegroups(
         &self,
         cluster_id: &str,
     ) -> Result<Vec<nodegroup::schemas::Nodegroup>, Error> {
         nodegroup::api::list(self, cluster_id)
+            .await
+            .map(Response::into_body)
     }
 
     /// Create a cluster nodegroup.
-    pub fn create_nodegroup(
+    pub async fn create_nodegroup(
         &self,
         cluster_id: &str,
         opts: &nodegroup::schemas::CreateOpts,
     ) -> Result<(), Error> {
         nodegroup::api::create(self, cluster_id, opts)
+            .await
+            .map(Response::into_body)
     }
 
     /// Delete a cluster nodegroup.
-    pub fn delete_nodegroup(&self, cluster_id: &str, nodegroup_id: &str) -> Result<(), Error> {
+    pub async fn delete_nodegroup(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+    ) -> Result<(), Error> {
         nodegroup::api::delete(self, cluster_id, nodegroup_id)
+            .await
+            .map(Response::into_body)
     }
 
     /// Resize a cluster nodegroup.
-    pub fn resize_nodegroup(
+    pub async fn resize_nodegroup(
         &self,
         cluster_id: &str,
         nodegroup_id: &str,
         opts: &nodegroup::schemas::ResizeOpts,
     ) -> Result<(), Error> {
         nodegroup::api::resize(self, cluster_id, nodegroup_id, opts)
+            .await
+            .map(Response::into_body)
     }
 
     /// Update a cluster nodegroup.
-    pub fn update_nodegroup(
+    pub async fn update_nodegroup(
         &self,
         cluster_id: &str,
         nodegroup_id: &str,
         opts: &nodegroup::schemas::UpdateOpts,
     ) -> Result<(), Error> {
         nodegroup::api::update(self, cluster_id, nodegroup_id, opts)
+            .await
+            .map(Response::into_body)
     }
 }
 
 /// Methods to work with tasks.
 impl Client {
     /// Get a task.
-    pub fn get_task(&self, cluster_id: &str, task_id: &str) -> Result<task::schemas::Task, Error> {
+    pub async fn get_task(
+        &self,
+        cluster_id: &str,
+        task_id: &str,
+    ) -> Result<task::schemas::Task, Error> {
         task::api::get(self, cluster_id, task_id)
+            .await
+            .map(Response::into_body)
     }
 
     /// List tasks.
-    pub fn list_tasks(&self, cluster_id: &str) -> Result<Vec<task::schemas::Task>, Error> {
+    pub async fn list_tasks(&self, cluster_id: &str) -> Result<Vec<task::schemas::Task>, Error> {
         task::api::list(self, cluster_id)
+            .await
+            .map(Response::into_body)
+    }
+}
+
+/// Methods to send requests that aren't wrapped by the client yet.
+impl Client {
+    /// Send a request to the MKS API and deserialize the JSON response body.
+    ///
+    /// Path is relative to the base endpoint, for example "/v1/clusters/{cluster_id}".
+    /// Absolute URLs are rejected with `Error::PathError`.
+    /// Body is serialized into JSON, use `None::<&()>` for requests without a body.
+    pub async fn request<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T, Error> {
+        raw::request(self, method, path, body)
+            .await?
+            .json()
+            .map(Response::into_body)
+    }
+
+    /// Send a request to the MKS API and get the raw response body.
+    ///
+    /// See `request` for details.
+    pub async fn request_raw<B: Serialize>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<Bytes, Error> {
+        raw::request(self, method, path, body)
+            .await
+            .map(Response::into_body)
     }
 }
 
 /// Builder for `Client`.
+#[derive(Clone)]
 pub struct Builder {
-    /// Hyper client to use for the connection.
-    client: Option<hyper::Client<HttpsConnector>>,
+    /// Transport to use for requests.
+    transport: Option<Arc<dyn Transport>>,
+
+    /// Ordered chain of request middlewares.
+    middlewares: Vec<Arc<dyn Middleware>>,
+
+    /// Recorder for request metrics.
+    metrics: Option<Arc<dyn MetricsRecorder>>,
+
+    /// Proxies to send requests through.
+    proxies: Vec<Proxy>,
+
+    /// Read proxies from the environment variables.
+    env_proxy: bool,
+
+    /// Overridden endpoints of regions.
+    region_endpoints: HashMap<Region, String>,
+
+    /// TLS settings of the default transport.
+    tls: TlsConfig,
 
     /// Request timeout.
     timeout: Duration,
+
+    /// Policy for retrying failed requests.
+    retry_policy: RetryPolicy,
+
+    /// Check responses against the schema.
+    strict_schemas: bool,
+
+    /// Receiver of schema warnings.
+    schema_reporter: Option<Arc<dyn SchemaReporter>>,
 }
 
 // Default timeout for requests.
@@ -307,22 +620,142 @@ const DEFAULT_TIMEOUT: u64 = 30;
 impl Default for Builder {
     fn default() -> Self {
         Self {
-            client: None,
+            transport: None,
+            middlewares: Vec::new(),
+            metrics: None,
+            proxies: Vec::new(),
+            env_proxy: true,
+            region_endpoints: HashMap::new(),
+            tls: TlsConfig::default(),
             timeout: Duration::from_secs(DEFAULT_TIMEOUT),
+            retry_policy: RetryPolicy::default(),
+            strict_schemas: false,
+            schema_reporter: None,
         }
     }
 }
 
 impl Builder {
+    // Use the provided transport or configure a new Hyper one.
+    // Explicitly set proxies take precedence over the environment ones.
+    fn make_transport(&self) -> Result<Arc<dyn Transport>, Error> {
+        if let Some(transport) = &self.transport {
+            return Ok(transport.clone());
+        }
+
+        let mut proxies = self.proxies.clone();
+        if proxies.is_empty() && self.env_proxy {
+            proxies = Proxy::from_env();
+        }
+
+        if proxies.is_empty() {
+            let connector = self.tls.https_connector()?;
+            Ok(Arc::new(HyperTransport::from_client(
+                hyper::Client::builder().build(connector),
+            )))
+        } else {
+            Ok(Arc::new(ProxyTransport::new(proxies, &self.tls)?))
+        }
+    }
+
     /// Set Hyper client.
     ///
     /// By default this library will instantiate a new HttpsConnector.
     /// It will use hyper_rustls or hyper_tls depending on selected library features.
-    pub fn with_client(mut self, client: hyper::Client<HttpsConnector>) -> Self {
-        self.client = Some(client);
+    pub fn with_client(self, client: hyper::Client<HttpsConnector>) -> Self {
+        self.with_transport(HyperTransport::from_client(client))
+    }
+
+    /// Set transport that will be used to send requests.
+    ///
+    /// By default `HyperTransport` is used.
+    /// Pass `Arc<T>` to keep access to the transport after the client is built.
+    pub fn with_transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Add middleware to the end of the request middleware chain.
+    ///
+    /// Middlewares are called in the same order they are added.
+    pub fn with_middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Set recorder for request metrics.
+    ///
+    /// Enable the `metrics` feature and use `metrics::MetricsCrateRecorder`
+    /// to report them with the `metrics` crate.
+    pub fn with_metrics<M: MetricsRecorder + 'static>(mut self, metrics: M) -> Self {
+        self.metrics = Some(Arc::new(metrics));
+        self
+    }
+
+    /// Add proxy to send requests through.
+    ///
+    /// Proxies are checked in the same order they are added, the first matching
+    /// one is used. Proxies from the environment are ignored if any proxy is added.
+    /// Proxies aren't used with a custom client or transport.
+    pub fn with_proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Enable or disable proxies from the environment variables.
+    ///
+    /// By default proxies are read from `HTTPS_PROXY`, `HTTP_PROXY` and `NO_PROXY`
+    /// variables or their lowercase versions.
+    pub fn with_env_proxy(mut self, env_proxy: bool) -> Self {
+        self.env_proxy = env_proxy;
+        self
+    }
+
+    /// Add trusted root certificate.
+    ///
+    /// Certificates are used in addition to the system ones unless they are disabled.
+    /// TLS settings aren't used with a custom client or transport.
+    pub fn with_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.tls.root_certificates.push(certificate);
+        self
+    }
+
+    /// Enable or disable system root certificates.
+    ///
+    /// Default is true.
+    pub fn with_system_roots(mut self, system_roots: bool) -> Self {
+        self.tls.system_roots = system_roots;
         self
     }
 
+    /// Set client certificate for mTLS.
+    pub fn with_identity(mut self, identity: Identity) -> Self {
+        self.tls.identity = Some(identity);
+        self
+    }
+
+    /// Override MKS endpoint of the region.
+    ///
+    /// It's useful for private or staging installations.
+    pub fn with_region_endpoint(mut self, region: Region, endpoint: &str) -> Self {
+        self.region_endpoints.insert(region, String::from(endpoint));
+        self
+    }
+
+    // Get MKS endpoint of the region.
+    fn region_endpoint(&self, region: &Region) -> Result<String, Error> {
+        self.region_endpoints
+            .get(region)
+            .cloned()
+            .or_else(|| region.endpoint())
+            .ok_or_else(|| {
+                Error::RegionError(format!(
+                    "{} (set its endpoint with Builder::with_region_endpoint)",
+                    region
+                ))
+            })
+    }
+
     /// Set request timeout.
     ///
     /// Default is 30 seconds.
@@ -331,9 +764,132 @@ impl Builder {
         self
     }
 
+    /// Set policy for retrying failed requests.
+    ///
+    /// Default is `RetryPolicy::default()`, use `RetryPolicy::none()` to disable retries.
+    /// Request timeout is applied to each attempt separately.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Enable or disable strict schema mode.
+    ///
+    /// In strict mode every response is checked for missing and unexpected fields
+    /// and unknown enum values. Differences are returned as `Error::SchemaError`
+    /// unless a schema reporter is set. Default is false.
+    pub fn with_strict_schemas(mut self, strict_schemas: bool) -> Self {
+        self.strict_schemas = strict_schemas;
+        self
+    }
+
+    /// Set reporter for schema warnings.
+    ///
+    /// It enables strict schema mode, but responses with warnings are
+    /// returned as usual after the warnings are reported.
+    pub fn with_schema_reporter<R: SchemaReporter + 'static>(mut self, reporter: R) -> Self {
+        self.schema_reporter = Some(Arc::new(reporter));
+        self
+    }
+
     /// Create `Client` with the configuration in this builder.
+    ///
+    /// Base endpoint can contain a path prefix, for example "https://gw.internal/mks/".
+    /// A trailing slash is added to the prefix if it's missing.
     pub fn build(self, base_endpoint: &str, token: &str) -> Result<Client, Error> {
-        Client::with_builder(base_endpoint, token, self)
+        let token_provider = StaticToken::new(token)?;
+        Client::with_builder(base_endpoint, Arc::new(token_provider), self)
+    }
+
+    /// Create `Client` for the region with the configuration in this builder.
+    ///
+    /// Endpoints of `Region::Other` regions must be set with `with_region_endpoint`,
+    /// otherwise `Error::RegionError` is returned.
+    pub fn build_for_region(self, region: Region, token: &str) -> Result<Client, Error> {
+        let base_endpoint = self.region_endpoint(&region)?;
+        self.build(&base_endpoint, token)
+    }
+
+    /// Create `MultiRegionClient` with clients for the regions.
+    ///
+    /// Every regional client uses the configuration in this builder.
+    pub fn build_multi_region(
+        self,
+        regions: &[Region],
+        token: &str,
+    ) -> Result<MultiRegionClient, Error> {
+        let clients = regions
+            .iter()
+            .map(|region| {
+                let client = self.clone().build_for_region(region.clone(), token)?;
+                Ok((region.clone(), client))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(MultiRegionClient::from_clients(clients))
+    }
+
+    /// Create `Client` that gets tokens from the provided `TokenProvider`.
+    ///
+    /// Use `blocking::Client::from_async` to wrap it into the blocking client.
+    pub fn build_with_token_provider<T: TokenProvider + 'static>(
+        self,
+        base_endpoint: &str,
+        token_provider: T,
+    ) -> Result<Client, Error> {
+        Client::with_builder(base_endpoint, Arc::new(token_provider), self)
+    }
+
+    /// Create `Client` that gets tokens from Keystone.
+    ///
+    /// Base endpoint is the public MKS endpoint of the region from the token service catalog.
+    /// Keystone requests are sent with the client transport unless `KeystoneAuth` has its own.
+    pub async fn build_with_keystone(
+        mut self,
+        auth: KeystoneAuth,
+        region: &str,
+    ) -> Result<Client, Error> {
+        let transport = self.make_transport()?;
+        self.transport = Some(transport.clone());
+
+        let auth = auth.with_default_transport(transport);
+        let base_endpoint = auth.endpoint(region).await?;
+
+        self.build_with_token_provider(&base_endpoint, auth)
+    }
+
+    /// Create `blocking::Client` with the configuration in this builder.
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking(
+        self,
+        base_endpoint: &str,
+        token: &str,
+    ) -> Result<blocking::Client, Error> {
+        blocking::Client::from_async(self.build(base_endpoint, token)?)
+    }
+
+    /// Create `blocking::Client` that gets tokens from Keystone.
+    ///
+    /// See `build_with_keystone` for details.
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking_with_keystone(
+        self,
+        auth: KeystoneAuth,
+        region: &str,
+    ) -> Result<blocking::Client, Error> {
+        blocking::Client::with_keystone(self, auth, region)
+    }
+
+    /// Create `blocking::MultiRegionClient` with clients for the regions.
+    ///
+    /// Every regional client uses the configuration in this builder.
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking_multi_region(
+        self,
+        regions: &[Region],
+        token: &str,
+    ) -> Result<blocking::MultiRegionClient, Error> {
+        blocking::MultiRegionClient::from_async(self.build_multi_region(regions, token)?)
     }
 }
 
@@ -341,23 +897,25 @@ impl Builder {
 mod tests {
     use super::*;
 
-    #[test]
-    fn new_client_default_builder() {
+    #[tokio::test]
+    async fn new_client_default_builder() {
         let client = Client::new("https://example.org", "token_a").unwrap();
 
         assert_eq!(
             client.base_endpoint,
             Url::parse("https://example.org").unwrap()
         );
-        assert_eq!(client.token, String::from("token_a"));
+        assert_eq!(client.token_provider.token().await.unwrap(), "token_a");
         assert_eq!(client.user_agent, format!("{}/{}", PKG_NAME, PKG_VERSION));
         assert_eq!(client.timeout, Duration::from_secs(DEFAULT_TIMEOUT));
+        assert_eq!(client.retry_policy, RetryPolicy::default());
     }
 
-    #[test]
-    fn new_client_with_builder() {
+    #[tokio::test]
+    async fn new_client_with_builder() {
         let client = Client::builder()
             .with_timeout(Duration::from_secs(10))
+            .with_retry_policy(RetryPolicy::none())
             .build("https://example.com", "token_b")
             .unwrap();
 
@@ -365,8 +923,581 @@ mod tests {
             client.base_endpoint,
             Url::parse("https://example.com").unwrap()
         );
-        assert_eq!(client.token, String::from("token_b"));
+        assert_eq!(client.token_provider.token().await.unwrap(), "token_b");
         assert_eq!(client.user_agent, format!("{}/{}", PKG_NAME, PKG_VERSION));
         assert_eq!(client.timeout, Duration::from_secs(10));
+        assert_eq!(client.retry_policy, RetryPolicy::none());
+    }
+
+    #[test]
+    fn new_client_for_region() {
+        let client = Client::for_region(Region::Ru7, "token_a").unwrap();
+        assert_eq!(
+            client.base_endpoint,
+            Url::parse("https://ru-7.mks.selcloud.ru/").unwrap()
+        );
+
+        let client = Client::builder()
+            .with_region_endpoint(Region::Ru7, "https://staging.internal/mks")
+            .build_for_region(Region::Ru7, "token_a")
+            .unwrap();
+        assert_eq!(
+            client.base_endpoint,
+            Url::parse("https://staging.internal/mks/").unwrap()
+        );
+
+        let private = Region::Other(String::from("ru-private"));
+        assert!(matches!(
+            Client::for_region(private.clone(), "token_a"),
+            Err(Error::RegionError(_))
+        ));
+        let client = Client::builder()
+            .with_region_endpoint(private.clone(), "https://mks.private.internal")
+            .build_for_region(private, "token_a")
+            .unwrap();
+        assert_eq!(
+            client.base_endpoint,
+            Url::parse("https://mks.private.internal/").unwrap()
+        );
+    }
+
+    #[test]
+    fn make_uri_without_base_path() {
+        let client = Client::new("https://example.org", "token_a").unwrap();
+
+        assert_eq!(
+            client.make_uri("/v1/clusters").unwrap(),
+            "https://example.org/v1/clusters"
+        );
+    }
+
+    #[test]
+    fn make_uri_rejects_absolute_paths() {
+        let client = Client::new("https://example.org", "token_a").unwrap();
+
+        for path in &["https://attacker.org/x", "//attacker.org/x", "mailto:x"] {
+            assert!(matches!(client.make_uri(path), Err(Error::PathError(_))));
+        }
+    }
+
+    #[test]
+    fn make_uri_with_base_path() {
+        let client = Client::new("https://gw.internal/mks/", "token_a").unwrap();
+
+        assert_eq!(
+            client.base_endpoint,
+            Url::parse("https://gw.internal/mks/").unwrap()
+        );
+        assert_eq!(
+            client.make_uri("/v1/clusters/cluster_a").unwrap(),
+            "https://gw.internal/mks/v1/clusters/cluster_a"
+        );
+    }
+
+    #[test]
+    fn make_uri_adds_trailing_slash() {
+        let client = Client::new("http://localhost:8080/api/mks", "token_a").unwrap();
+
+        assert_eq!(
+            client.base_endpoint,
+            Url::parse("http://localhost:8080/api/mks/").unwrap()
+        );
+        assert_eq!(
+            client.make_uri("/v1/kubeversions").unwrap(),
+            "http://localhost:8080/api/mks/v1/kubeversions"
+        );
+    }
+
+    #[test]
+    fn new_client_bad_endpoint() {
+        let err = Client::new("example.org/mks", "token_a").err().unwrap();
+
+        assert!(matches!(err, Error::EndpointError(_)));
+    }
+
+    // Subscriber that keeps all recorded span and event fields.
+    #[derive(Default)]
+    struct FieldsRecorder {
+        fields: std::sync::Mutex<Vec<String>>,
+        spans: std::sync::Mutex<Vec<&'static tracing::Metadata<'static>>>,
+        stack: std::sync::Mutex<Vec<tracing::span::Id>>,
+    }
+
+    impl tracing::field::Visit for &FieldsRecorder {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            let field = format!("{}={:?}", field.name(), value);
+            self.fields.lock().unwrap().push(field);
+        }
+    }
+
+    impl tracing::Subscriber for &'static FieldsRecorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut visitor = *self;
+            span.record(&mut visitor);
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(span.metadata());
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            let mut visitor = *self;
+            values.record(&mut visitor);
+        }
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut visitor = *self;
+            event.record(&mut visitor);
+        }
+
+        fn enter(&self, span: &tracing::span::Id) {
+            self.stack.lock().unwrap().push(span.clone());
+        }
+
+        fn exit(&self, _: &tracing::span::Id) {
+            self.stack.lock().unwrap().pop();
+        }
+
+        fn current_span(&self) -> tracing_core::span::Current {
+            match self.stack.lock().unwrap().last() {
+                Some(id) => {
+                    let metadata = self.spans.lock().unwrap()[id.into_u64() as usize - 1];
+                    tracing_core::span::Current::new(id.clone(), metadata)
+                }
+                None => tracing_core::span::Current::none(),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn request_span_fields() {
+        let recorder: &'static FieldsRecorder = Box::leak(Box::new(FieldsRecorder::default()));
+        let _guard = tracing::subscriber::set_default(recorder);
+
+        let transport = Arc::new(
+            transport::InMemoryTransport::new()
+                .with_response(503, "")
+                .with_response(404, ""),
+        );
+        retry_client(&transport)
+            .get_nodegroup("cluster_a", "nodegroup_a")
+            .await
+            .unwrap_err();
+
+        let fields = recorder.fields.lock().unwrap().clone();
+        assert!(fields.contains(&String::from("method=\"GET\"")));
+        assert!(fields.contains(&String::from(
+            "path=\"/v1/clusters/{cluster_id}/nodegroups/{nodegroup_id}\""
+        )));
+        assert!(fields.contains(&String::from("cluster_id=\"cluster_a\"")));
+        assert!(fields.contains(&String::from("nodegroup_id=\"nodegroup_a\"")));
+        assert!(fields.contains(&String::from("status=404")));
+        assert!(fields.contains(&String::from("retries=1")));
+        assert!(fields.iter().any(|f| f.starts_with("latency_ms=")));
+        assert!(fields.iter().all(|f| !f.contains("token_a")));
+    }
+
+    struct TenantHeader;
+
+    impl Middleware for TenantHeader {
+        fn handle<'a>(
+            &'a self,
+            mut req: Request<hyper::Body>,
+            next: Next<'a>,
+        ) -> transport::ResponseFuture<'a> {
+            req.headers_mut()
+                .insert("x-tenant", HeaderValue::from_static("tenant_a"));
+            next.run(req)
+        }
+    }
+
+    #[tokio::test]
+    async fn request_with_middleware() {
+        let transport = Arc::new(
+            transport::InMemoryTransport::new().with_response(200, r#"{"kube_versions": []}"#),
+        );
+        let client = Client::builder()
+            .with_transport(transport.clone())
+            .with_middleware(TenantHeader)
+            .build("https://example.org", "token_a")
+            .unwrap();
+
+        client.list_kube_versions().await.unwrap();
+
+        assert_eq!(transport.requests()[0].headers["x-tenant"], "tenant_a");
+    }
+
+    #[derive(Default)]
+    struct StatusClasses(std::sync::Mutex<Vec<String>>);
+
+    impl MetricsRecorder for StatusClasses {
+        fn record(&self, metrics: &RequestMetrics<'_>) {
+            let record = format!(
+                "{} {} {} retries={}",
+                metrics.method,
+                metrics.endpoint,
+                metrics.status_class(),
+                metrics.retries
+            );
+            self.0.lock().unwrap().push(record);
+        }
+    }
+
+    #[tokio::test]
+    async fn request_metrics() {
+        let transport = Arc::new(
+            transport::InMemoryTransport::new()
+                .with_response(200, r#"{"kube_versions": []}"#)
+                .with_response(404, ""),
+        );
+        let metrics = Arc::new(StatusClasses::default());
+        let client = Client::builder()
+            .with_transport(transport.clone())
+            .with_metrics(metrics.clone())
+            .build("https://example.org", "token_a")
+            .unwrap();
+
+        client.list_kube_versions().await.unwrap();
+        client.delete_cluster("cluster_a").await.unwrap_err();
+
+        assert_eq!(
+            *metrics.0.lock().unwrap(),
+            vec![
+                "GET /v1/kubeversions 2xx retries=0",
+                "DELETE /v1/clusters/{cluster_id} 4xx retries=0",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn token_error_metrics() {
+        let transport = Arc::new(transport::InMemoryTransport::new());
+        let metrics = Arc::new(StatusClasses::default());
+        let client = Client::builder()
+            .with_transport(transport.clone())
+            .with_metrics(metrics.clone())
+            .build_with_token_provider(
+                "https://example.org",
+                token::FileToken::new("/nonexistent/mks-token"),
+            )
+            .unwrap();
+
+        let err = client.list_kube_versions().await.unwrap_err();
+
+        assert!(matches!(err, Error::TokenError(_)));
+        assert!(transport.requests().is_empty());
+        assert_eq!(
+            *metrics.0.lock().unwrap(),
+            vec!["GET /v1/kubeversions error retries=0"]
+        );
+    }
+
+    // Kubernetes versions with an unexpected field.
+    const DRIFTED_KUBE_VERSIONS: &str =
+        r#"{"kube_versions": [{"version": "1.17.9", "is_default": true, "deprecated": false}]}"#;
+
+    #[tokio::test]
+    async fn strict_schemas() {
+        let transport = transport::InMemoryTransport::new()
+            .with_response(200, DRIFTED_KUBE_VERSIONS)
+            .with_response(200, r#"{"kube_versions": []}"#);
+        let client = Client::builder()
+            .with_transport(transport)
+            .with_strict_schemas(true)
+            .build("https://example.org", "token_a")
+            .unwrap();
+
+        match client.list_kube_versions().await.unwrap_err() {
+            Error::SchemaError(warnings) => {
+                assert_eq!(warnings.len(), 1);
+                assert_eq!(warnings[0].field, "kube_versions[0].deprecated");
+                assert_eq!(warnings[0].kind, schema::SchemaWarningKind::UnexpectedField);
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+        assert!(client.list_kube_versions().await.unwrap().is_empty());
+    }
+
+    #[derive(Default)]
+    struct SchemaWarnings(std::sync::Mutex<Vec<String>>);
+
+    impl SchemaReporter for SchemaWarnings {
+        fn report(&self, warnings: &[schema::SchemaWarning]) {
+            let mut reported = self.0.lock().unwrap();
+            reported.extend(warnings.iter().map(|w| w.to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn report_schema_warnings() {
+        let transport =
+            transport::InMemoryTransport::new().with_response(200, DRIFTED_KUBE_VERSIONS);
+        let warnings = Arc::new(SchemaWarnings::default());
+        let client = Client::builder()
+            .with_transport(transport)
+            .with_schema_reporter(warnings.clone())
+            .build("https://example.org", "token_a")
+            .unwrap();
+
+        let kube_versions = client.list_kube_versions().await.unwrap();
+
+        assert_eq!(kube_versions.len(), 1);
+        assert_eq!(
+            *warnings.0.lock().unwrap(),
+            vec!["/v1/kubeversions: unexpected field kube_versions[0].deprecated"]
+        );
+    }
+
+    fn retry_client(transport: &Arc<transport::InMemoryTransport>) -> Client {
+        let retry_policy = RetryPolicy::new()
+            .with_initial_backoff(Duration::from_millis(1))
+            .with_max_retries(2);
+
+        Client::builder()
+            .with_transport(transport.clone())
+            .with_retry_policy(retry_policy)
+            .build("https://example.org", "token_a")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn retry_idempotent_request() {
+        let transport = Arc::new(
+            transport::InMemoryTransport::new()
+                .with_response(503, "")
+                .with_response(429, "")
+                .with_response(200, r#"{"kube_versions": []}"#),
+        );
+
+        let versions = retry_client(&transport).list_kube_versions().await.unwrap();
+
+        assert!(versions.is_empty());
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn retry_after_is_capped() {
+        let throttled = hyper::Response::builder()
+            .status(429)
+            .header(hyper::header::RETRY_AFTER, "86400")
+            .body(Bytes::new())
+            .unwrap();
+        let transport = transport::InMemoryTransport::new()
+            .with_http_response(throttled)
+            .with_response(200, r#"{"kube_versions": []}"#);
+        let client = Client::builder()
+            .with_transport(transport)
+            .with_retry_policy(RetryPolicy::new().with_max_backoff(Duration::from_millis(10)))
+            .build("https://example.org", "token_a")
+            .unwrap();
+
+        let versions = timeout(Duration::from_secs(5), client.list_kube_versions())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(versions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn retry_stops_after_max_retries() {
+        let transport = Arc::new(
+            transport::InMemoryTransport::new()
+                .with_response(502, "")
+                .with_response(502, "")
+                .with_response(502, ""),
+        );
+
+        let err = retry_client(&transport)
+            .list_kube_versions()
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.status_code(), Some(502));
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn no_retry_for_non_idempotent_request() {
+        let transport = Arc::new(transport::InMemoryTransport::new().with_response(503, ""));
+
+        let err = retry_client(&transport)
+            .reinstall_node("cluster", "nodegroup", "node")
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.status_code(), Some(503));
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    // RotatingToken provides a new token on every refresh.
+    struct RotatingToken {
+        refreshes: std::sync::Mutex<u32>,
+    }
+
+    impl TokenProvider for RotatingToken {
+        fn token(&self) -> token::TokenFuture<'_> {
+            Box::pin(async move { Ok(format!("token_{}", self.refreshes.lock().unwrap())) })
+        }
+
+        fn refresh<'a>(&'a self, _rejected: &'a str) -> token::RefreshFuture<'a> {
+            *self.refreshes.lock().unwrap() += 1;
+            Box::pin(async move { self.token().await.map(Some) })
+        }
+    }
+
+    fn rotating_token_client(transport: &Arc<transport::InMemoryTransport>) -> Client {
+        let token_provider = RotatingToken {
+            refreshes: std::sync::Mutex::new(0),
+        };
+
+        Client::builder()
+            .with_transport(transport.clone())
+            .with_retry_policy(RetryPolicy::none())
+            .build_with_token_provider("https://example.org", token_provider)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn refresh_rejected_token() {
+        let transport = Arc::new(
+            transport::InMemoryTransport::new()
+                .with_response(401, "")
+                .with_response(204, ""),
+        );
+
+        rotating_token_client(&transport)
+            .reinstall_node("cluster", "nodegroup", "node")
+            .await
+            .unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].headers[AUTH_TOKEN_HEADER], "token_0");
+        assert_eq!(requests[1].headers[AUTH_TOKEN_HEADER], "token_1");
+    }
+
+    #[tokio::test]
+    async fn refresh_rejected_token_once() {
+        let transport = Arc::new(
+            transport::InMemoryTransport::new()
+                .with_response(401, "")
+                .with_response(401, ""),
+        );
+
+        let err = rotating_token_client(&transport)
+            .list_kube_versions()
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.status_code(), Some(401));
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn no_refresh_for_static_token() {
+        let transport = Arc::new(
+            transport::InMemoryTransport::new()
+                .with_response(401, "")
+                .with_response(204, ""),
+        );
+        let client = Client::builder()
+            .with_transport(transport.clone())
+            .with_retry_policy(RetryPolicy::none())
+            .build("https://example.org", "token_a")
+            .unwrap();
+
+        let err = client.list_kube_versions().await.unwrap_err();
+
+        assert_eq!(err.status_code(), Some(401));
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn no_retry_with_same_refreshed_token() {
+        let transport = Arc::new(
+            transport::InMemoryTransport::new()
+                .with_response(401, "")
+                .with_response(204, ""),
+        );
+        let path = std::env::temp_dir().join(format!("mks-same-token-{}", std::process::id()));
+        std::fs::write(&path, "token_a").unwrap();
+        let client = Client::builder()
+            .with_transport(transport.clone())
+            .with_retry_policy(RetryPolicy::none())
+            .build_with_token_provider("https://example.org", token::FileToken::new(&path))
+            .unwrap();
+
+        let err = client.list_kube_versions().await.unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(err.status_code(), Some(401));
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    // Accept a single proxy connection, reply to its first request and return it.
+    async fn fake_proxy(reply: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_url = format!("http://user:pass@{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            let n = stream.read(&mut buf).await.unwrap();
+            stream.write_all(reply.as_bytes()).await.unwrap();
+
+            String::from_utf8_lossy(&buf[..n]).to_lowercase()
+        });
+
+        (proxy_url, handle)
+    }
+
+    fn proxy_client(proxy_url: &str, base_endpoint: &str) -> Client {
+        Client::builder()
+            .with_env_proxy(false)
+            .with_proxy(Proxy::all(proxy_url).unwrap())
+            .with_retry_policy(RetryPolicy::none())
+            .build(base_endpoint, "token_a")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn http_request_through_proxy() {
+        let (proxy_url, proxy) =
+            fake_proxy("HTTP/1.1 200 OK\r\ncontent-length: 21\r\n\r\n{\"kube_versions\": []}")
+                .await;
+
+        let kube_versions = proxy_client(&proxy_url, "http://mks.internal")
+            .list_kube_versions()
+            .await
+            .unwrap();
+        assert!(kube_versions.is_empty());
+
+        let request = proxy.await.unwrap();
+        assert!(request.starts_with("get http://mks.internal/v1/kubeversions http/1.1\r\n"));
+        assert!(request.contains("proxy-authorization: basic dxnlcjpwyxnz\r\n"));
+    }
+
+    #[tokio::test]
+    async fn https_request_through_proxy() {
+        let (proxy_url, proxy) =
+            fake_proxy("HTTP/1.1 403 Forbidden\r\ncontent-length: 0\r\n\r\n").await;
+
+        let err = proxy_client(&proxy_url, "https://mks.internal")
+            .list_kube_versions()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::HyperError(_)));
+
+        let request = proxy.await.unwrap();
+        assert!(request.starts_with("connect mks.internal:443 http/1.1\r\n"));
+        assert!(request.contains("proxy-authorization: basic dxnlcjpwyxnz\r\n"));
     }
 }
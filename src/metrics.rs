@@ -0,0 +1,223 @@
+use hyper::Method;
+use std::time::Duration;
+
+/// RequestMetrics represents measurements of a single API call.
+///
+/// It's reported once per call after all retries are finished.
+#[derive(Debug)]
+pub struct RequestMetrics<'a> {
+    /// Request method.
+    pub method: &'a Method,
+
+    /// Request path template, for example "/v1/clusters/{cluster_id}".
+    /// It's "custom" for requests sent with `Client::request`.
+    pub endpoint: &'a str,
+
+    /// Response status code if the response has been received.
+    pub status: Option<u16>,
+
+    /// Total time of the call including all retries.
+    pub latency: Duration,
+
+    /// Number of retries after the first attempt.
+    pub retries: u32,
+
+    /// Flag that indicates if the last attempt timed out.
+    pub timed_out: bool,
+}
+
+impl<'a> RequestMetrics<'a> {
+    /// Get class of the response status code: "2xx", "4xx", "5xx", etc.
+    ///
+    /// Returns "timeout" or "error" if the response has not been received.
+    pub fn status_class(&self) -> &'static str {
+        match self.status {
+            Some(100..=199) => "1xx",
+            Some(200..=299) => "2xx",
+            Some(300..=399) => "3xx",
+            Some(400..=499) => "4xx",
+            Some(500..=599) => "5xx",
+            Some(_) => "unknown",
+            None if self.timed_out => "timeout",
+            None => "error",
+        }
+    }
+}
+
+/// `MetricsRecorder` receives metrics of every API call made by the `Client`.
+pub trait MetricsRecorder: Send + Sync {
+    /// Record metrics of a finished call.
+    fn record(&self, metrics: &RequestMetrics<'_>);
+}
+
+impl<M: MetricsRecorder + ?Sized> MetricsRecorder for std::sync::Arc<M> {
+    fn record(&self, metrics: &RequestMetrics<'_>) {
+        (**self).record(metrics)
+    }
+}
+
+/// `MetricsCrateRecorder` reports metrics with the `metrics` crate.
+///
+/// It's available with the `metrics` feature. The following metrics are
+/// reported with `method`, `endpoint` and `status_class` labels:
+///  - `mks_client_requests_total` counter;
+///  - `mks_client_request_duration_seconds` histogram;
+///  - `mks_client_retries_total` counter;
+///  - `mks_client_timeouts_total` counter.
+#[cfg(feature = "metrics")]
+#[derive(Clone, Debug, Default)]
+pub struct MetricsCrateRecorder;
+
+#[cfg(feature = "metrics")]
+impl MetricsRecorder for MetricsCrateRecorder {
+    fn record(&self, metrics: &RequestMetrics<'_>) {
+        let labels = [
+            ("method", metrics.method.to_string()),
+            ("endpoint", metrics.endpoint.to_string()),
+            ("status_class", metrics.status_class().to_string()),
+        ];
+
+        metrics_rs::counter!("mks_client_requests_total", &labels).increment(1);
+        metrics_rs::histogram!("mks_client_request_duration_seconds", &labels)
+            .record(metrics.latency.as_secs_f64());
+        metrics_rs::counter!("mks_client_retries_total", &labels)
+            .increment(u64::from(metrics.retries));
+        if metrics.timed_out {
+            metrics_rs::counter!("mks_client_timeouts_total", &labels).increment(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_classes() {
+        let mut metrics = RequestMetrics {
+            method: &Method::GET,
+            endpoint: "/v1/clusters",
+            status: Some(200),
+            latency: Duration::from_millis(10),
+            retries: 0,
+            timed_out: false,
+        };
+        assert_eq!(metrics.status_class(), "2xx");
+
+        metrics.status = Some(404);
+        assert_eq!(metrics.status_class(), "4xx");
+
+        metrics.status = Some(503);
+        assert_eq!(metrics.status_class(), "5xx");
+
+        metrics.status = None;
+        assert_eq!(metrics.status_class(), "error");
+
+        metrics.timed_out = true;
+        assert_eq!(metrics.status_class(), "timeout");
+    }
+
+    #[cfg(feature = "metrics")]
+    mod metrics_crate {
+        use super::*;
+        use metrics_rs::{
+            Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+            SharedString, Unit,
+        };
+        use std::sync::{Arc, Mutex};
+
+        // LogRecorder writes every recorded value with its metric key into the log.
+        #[derive(Default)]
+        struct LogRecorder {
+            log: Arc<Mutex<Vec<String>>>,
+        }
+
+        struct LogHandle {
+            key: String,
+            log: Arc<Mutex<Vec<String>>>,
+        }
+
+        impl LogRecorder {
+            fn handle(&self, key: &Key) -> Arc<LogHandle> {
+                let labels: Vec<String> = key
+                    .labels()
+                    .map(|label| format!("{}={}", label.key(), label.value()))
+                    .collect();
+
+                Arc::new(LogHandle {
+                    key: format!("{}{{{}}}", key.name(), labels.join(",")),
+                    log: self.log.clone(),
+                })
+            }
+        }
+
+        impl Recorder for LogRecorder {
+            fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+            fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+            fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+            fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+                Counter::from_arc(self.handle(key))
+            }
+
+            fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+                Gauge::noop()
+            }
+
+            fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+                Histogram::from_arc(self.handle(key))
+            }
+        }
+
+        impl CounterFn for LogHandle {
+            fn increment(&self, value: u64) {
+                self.log
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} += {}", self.key, value));
+            }
+
+            fn absolute(&self, value: u64) {
+                self.log
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} = {}", self.key, value));
+            }
+        }
+
+        impl HistogramFn for LogHandle {
+            fn record(&self, value: f64) {
+                self.log
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} <- {}", self.key, value));
+            }
+        }
+
+        #[test]
+        fn metrics_crate_recorder() {
+            let recorder = LogRecorder::default();
+            let metrics = RequestMetrics {
+                method: &Method::GET,
+                endpoint: "/v1/clusters/{cluster_id}",
+                status: None,
+                latency: Duration::from_millis(1500),
+                retries: 2,
+                timed_out: true,
+            };
+
+            metrics_rs::with_local_recorder(&recorder, || MetricsCrateRecorder.record(&metrics));
+
+            let labels = "{method=GET,endpoint=/v1/clusters/{cluster_id},status_class=timeout}";
+            assert_eq!(
+                *recorder.log.lock().unwrap(),
+                vec![
+                    format!("mks_client_requests_total{} += 1", labels),
+                    format!("mks_client_request_duration_seconds{} <- 1.5", labels),
+                    format!("mks_client_retries_total{} += 2", labels),
+                    format!("mks_client_timeouts_total{} += 1", labels),
+                ]
+            );
+        }
+    }
+}
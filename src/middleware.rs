@@ -0,0 +1,126 @@
+use hyper::{Body, Request};
+use std::sync::Arc;
+
+use super::transport::{ResponseFuture, Transport};
+
+/// `Middleware` intercepts every request sent by the `Client`.
+///
+/// Middleware can inspect or mutate the outgoing request before passing it
+/// to the `Next` handler and the response before it's deserialized.
+/// Middlewares are called in the same order they were added to the `Builder`,
+/// the first one receives the request first and the response last.
+///
+/// Middlewares are called for each attempt of the request when it's retried.
+pub trait Middleware: Send + Sync {
+    /// Handle a request and pass it to the rest of the chain.
+    fn handle<'a>(&'a self, req: Request<Body>, next: Next<'a>) -> ResponseFuture<'a>;
+}
+
+impl<M: Middleware + ?Sized> Middleware for Arc<M> {
+    fn handle<'a>(&'a self, req: Request<Body>, next: Next<'a>) -> ResponseFuture<'a> {
+        (**self).handle(req, next)
+    }
+}
+
+/// `Next` represents the rest of the middleware chain with the transport at its end.
+pub struct Next<'a> {
+    transport: &'a dyn Transport,
+    middlewares: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(
+        transport: &'a dyn Transport,
+        middlewares: &'a [Arc<dyn Middleware>],
+    ) -> Self {
+        Next {
+            transport,
+            middlewares,
+        }
+    }
+
+    /// Pass the request to the next middleware or send it with the transport.
+    pub fn run(self, req: Request<Body>) -> ResponseFuture<'a> {
+        match self.middlewares.split_first() {
+            Some((middleware, rest)) => middleware.handle(req, Next::new(self.transport, rest)),
+            None => self.transport.send(req),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::InMemoryTransport;
+    use hyper::header::HeaderValue;
+    use hyper::StatusCode;
+    use std::sync::Mutex;
+
+    struct Tag {
+        name: &'static str,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Middleware for Tag {
+        fn handle<'a>(&'a self, mut req: Request<Body>, next: Next<'a>) -> ResponseFuture<'a> {
+            Box::pin(async move {
+                self.calls
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} request", self.name));
+                req.headers_mut()
+                    .append("x-tag", HeaderValue::from_static(self.name));
+
+                let mut resp = next.run(req).await?;
+
+                self.calls
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} response", self.name));
+                *resp.status_mut() = StatusCode::ACCEPTED;
+
+                Ok(resp)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn ordered_chain() {
+        let transport = InMemoryTransport::new().with_response(200, "");
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let middlewares: Vec<Arc<dyn Middleware>> = vec![
+            Arc::new(Tag {
+                name: "first",
+                calls: calls.clone(),
+            }),
+            Arc::new(Tag {
+                name: "second",
+                calls: calls.clone(),
+            }),
+        ];
+
+        let resp = Next::new(&transport, &middlewares)
+            .run(Request::new(Body::empty()))
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::ACCEPTED);
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "first request",
+                "second request",
+                "second response",
+                "first response"
+            ]
+        );
+
+        let tags: Vec<_> = transport.requests()[0]
+            .headers
+            .get_all("x-tag")
+            .iter()
+            .cloned()
+            .collect();
+        assert_eq!(tags, vec!["first", "second"]);
+    }
+}
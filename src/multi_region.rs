@@ -0,0 +1,241 @@
+use futures::future::join_all;
+use std::future::Future;
+
+use super::cluster::schemas::Cluster;
+use super::error::Error;
+use super::region::Region;
+use super::Client;
+
+/// `MultiRegionClient` makes calls to the MKS API in several regions concurrently.
+///
+/// Every call is made in all regions, a failure in one region doesn't affect
+/// results of the other ones.
+pub struct MultiRegionClient {
+    clients: Vec<(Region, Client)>,
+}
+
+impl MultiRegionClient {
+    /// Construct the new MultiRegionClient with default clients for the provided regions.
+    ///
+    /// Use `Builder::build_multi_region` to configure clients.
+    pub fn new(regions: &[Region], token: &str) -> Result<MultiRegionClient, Error> {
+        Client::builder().build_multi_region(regions, token)
+    }
+
+    /// Construct the new MultiRegionClient from prepared clients.
+    pub fn from_clients(clients: Vec<(Region, Client)>) -> MultiRegionClient {
+        MultiRegionClient { clients }
+    }
+
+    /// Get client for the region.
+    pub fn client(&self, region: &Region) -> Option<&Client> {
+        self.clients
+            .iter()
+            .find(|(client_region, _)| client_region == region)
+            .map(|(_, client)| client)
+    }
+
+    /// Get regions of the client.
+    pub fn regions(&self) -> Vec<Region> {
+        self.clients
+            .iter()
+            .map(|(region, _)| region.clone())
+            .collect()
+    }
+
+    /// List clusters in all regions.
+    pub async fn list_clusters_all(&self) -> RegionResults<Vec<Cluster>> {
+        self.call(|client| client.list_clusters()).await
+    }
+
+    /// Find a cluster by its identifier in all regions.
+    ///
+    /// Regions that respond with 404 status code are skipped, other errors are reported
+    /// because the cluster might be in one of the failed regions.
+    pub async fn find_cluster(&self, cluster_id: &str) -> ClusterSearch {
+        let results = self.call(|client| client.get_cluster(cluster_id)).await;
+
+        let mut search = ClusterSearch {
+            found: None,
+            failures: Vec::new(),
+        };
+        for (region, result) in results.into_inner() {
+            match result {
+                Ok(cluster) => search.found = Some((region, cluster)),
+                Err(err) if err.is_not_found() => {}
+                Err(err) => search.failures.push((region, err)),
+            }
+        }
+
+        search
+    }
+
+    // Make the call with every regional client concurrently.
+    async fn call<'a, F, Fut, T>(&'a self, f: F) -> RegionResults<T>
+    where
+        F: Fn(&'a Client) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let calls = self.clients.iter().map(|(region, client)| {
+            let call = f(client);
+            async move { (region.clone(), call.await) }
+        });
+
+        RegionResults {
+            results: join_all(calls).await,
+        }
+    }
+}
+
+/// RegionResults represents results of a call made in several regions.
+#[derive(Debug)]
+pub struct RegionResults<T> {
+    results: Vec<(Region, Result<T, Error>)>,
+}
+
+impl<T> RegionResults<T> {
+    /// Get results of all regions in the order of the client regions.
+    pub fn iter(&self) -> impl Iterator<Item = &(Region, Result<T, Error>)> {
+        self.results.iter()
+    }
+
+    /// Get successful results.
+    pub fn successes(&self) -> impl Iterator<Item = (&Region, &T)> {
+        self.results
+            .iter()
+            .filter_map(|(region, result)| result.as_ref().ok().map(|value| (region, value)))
+    }
+
+    /// Get errors of the failed regions.
+    pub fn failures(&self) -> impl Iterator<Item = (&Region, &Error)> {
+        self.results
+            .iter()
+            .filter_map(|(region, result)| result.as_ref().err().map(|err| (region, err)))
+    }
+
+    /// Check if the call has succeeded in all regions.
+    pub fn is_complete(&self) -> bool {
+        self.results.iter().all(|(_, result)| result.is_ok())
+    }
+
+    /// Get results of all regions.
+    pub fn into_inner(self) -> Vec<(Region, Result<T, Error>)> {
+        self.results
+    }
+}
+
+/// ClusterSearch represents a result of the cluster search across regions.
+#[derive(Debug)]
+pub struct ClusterSearch {
+    /// Found cluster with its region.
+    pub found: Option<(Region, Cluster)>,
+
+    /// Errors of the regions where the search has failed.
+    pub failures: Vec<(Region, Error)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::CLUSTER;
+    use crate::retry::RetryPolicy;
+    use crate::transport::{InMemoryTransport, ResponseFuture, Transport};
+    use hyper::{Body, Request};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    // HangingTransport never responds.
+    struct HangingTransport;
+
+    impl Transport for HangingTransport {
+        fn send(&self, _req: Request<Body>) -> ResponseFuture<'_> {
+            Box::pin(futures::future::pending())
+        }
+    }
+
+    fn client<T: Transport + 'static>(transport: T) -> Client {
+        Client::builder()
+            .with_transport(transport)
+            .with_timeout(Duration::from_millis(50))
+            .with_retry_policy(RetryPolicy::none())
+            .build("https://example.org", "token_a")
+            .unwrap()
+    }
+
+    #[test]
+    fn new_multi_region_client() {
+        let multi_region = MultiRegionClient::new(&[Region::Ru1, Region::Ru3], "token_a").unwrap();
+
+        assert_eq!(multi_region.regions(), vec![Region::Ru1, Region::Ru3]);
+        assert_eq!(
+            multi_region
+                .client(&Region::Ru3)
+                .unwrap()
+                .base_endpoint
+                .as_str(),
+            "https://ru-3.mks.selcloud.ru/"
+        );
+        assert!(multi_region.client(&Region::Ru2).is_none());
+    }
+
+    #[tokio::test]
+    async fn list_clusters_with_partial_failure() {
+        let body = format!(r#"{{"clusters": [{}]}}"#, CLUSTER);
+        let multi_region = MultiRegionClient::from_clients(vec![
+            (
+                Region::Ru1,
+                client(InMemoryTransport::new().with_response(200, r#"{"clusters": []}"#)),
+            ),
+            (Region::Ru2, client(HangingTransport)),
+            (
+                Region::Ru3,
+                client(InMemoryTransport::new().with_response(200, &body)),
+            ),
+        ]);
+
+        let results = multi_region.list_clusters_all().await;
+
+        assert!(!results.is_complete());
+        let successes: Vec<_> = results
+            .successes()
+            .map(|(region, clusters)| (region, clusters.len()))
+            .collect();
+        assert_eq!(successes, vec![(&Region::Ru1, 0), (&Region::Ru3, 1)]);
+        let failures: Vec<_> = results.failures().collect();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, &Region::Ru2);
+        assert!(failures[0].1.is_timeout());
+    }
+
+    #[tokio::test]
+    async fn find_cluster() {
+        let not_found = r#"{"error": {"message": "cluster is not found"}}"#;
+        let body = format!(r#"{{"cluster": {}}}"#, CLUSTER);
+        let ru1 = Arc::new(InMemoryTransport::new().with_response(404, not_found));
+        let multi_region = MultiRegionClient::from_clients(vec![
+            (Region::Ru1, client(ru1.clone())),
+            (
+                Region::Ru2,
+                client(InMemoryTransport::new().with_response(500, "")),
+            ),
+            (
+                Region::Ru3,
+                client(InMemoryTransport::new().with_response(200, &body)),
+            ),
+        ]);
+
+        let search = multi_region
+            .find_cluster("dbe7559b-55d8-4f65-9230-6a22b985ff73")
+            .await;
+
+        let (region, cluster) = search.found.unwrap();
+        assert_eq!(region, Region::Ru3);
+        assert_eq!(cluster.name, "test-cluster");
+        assert_eq!(search.failures.len(), 1);
+        assert_eq!(search.failures[0].0, Region::Ru2);
+        assert_eq!(
+            ru1.requests()[0].uri,
+            "https://example.org/v1/clusters/dbe7559b-55d8-4f65-9230-6a22b985ff73"
+        );
+    }
+}
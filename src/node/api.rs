@@ -1,41 +1,42 @@
 use hyper::Method;
 
 use super::super::error::Error;
-use super::super::resource_url::{API_VERSION, CLUSTERS, NODEGROUPS, REINSTALL};
+use super::super::resource_url::{expand, NODE_PATH, NODE_REINSTALL_PATH};
+use super::super::response::Response;
 use super::super::Client;
 use super::schemas;
 
-pub fn get(
-    client: &Client,
-    cluster_id: &str,
-    nodegroup_id: &str,
-    node_id: &str,
-) -> Result<schemas::Node, Error> {
-    let path = format!(
-        "/{}/{}/{}/{}/{}/{}",
-        API_VERSION, CLUSTERS, cluster_id, NODEGROUPS, nodegroup_id, node_id
-    );
-    let req = client.new_request(Method::GET, &path, None)?;
-    let body = client.do_request(req)?;
+api_call! {
+    name = "mks.node.get",
+    skip(client),
+    pub async fn get(
+        client: &Client,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        node_id: &str,
+    ) -> Result<Response<schemas::Node>, Error> {
+        let path = expand(NODE_PATH, &[cluster_id, nodegroup_id, node_id])?;
+        let req = client.new_request(Method::GET, &path, None)?;
+        let deserialized: Response<schemas::NodeRoot> =
+            client.do_request_json(req, NODE_PATH).await?;
 
-    let deserialized: schemas::NodeRoot =
-        serde_json::from_str(&body).map_err(|err| Error::DeserializeError(err, body))?;
-
-    Ok(deserialized.node)
+        Ok(deserialized.map(|root| root.node))
+    }
 }
 
-pub fn reinstall(
-    client: &Client,
-    cluster_id: &str,
-    nodegroup_id: &str,
-    node_id: &str,
-) -> Result<(), Error> {
-    let path = format!(
-        "/{}/{}/{}/{}/{}/{}/{}",
-        API_VERSION, CLUSTERS, cluster_id, NODEGROUPS, nodegroup_id, node_id, REINSTALL
-    );
-    let req = client.new_request(Method::POST, &path, None)?;
-    client.do_request(req)?;
+api_call! {
+    name = "mks.node.reinstall",
+    skip(client),
+    pub async fn reinstall(
+        client: &Client,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        node_id: &str,
+    ) -> Result<Response<()>, Error> {
+        let path = expand(NODE_REINSTALL_PATH, &[cluster_id, nodegroup_id, node_id])?;
+        let req = client.new_request(Method::POST, &path, None)?;
+        let resp = client.do_request(req, NODE_REINSTALL_PATH).await?;
 
-    Ok(())
+        Ok(resp.map(|_| ()))
+    }
 }
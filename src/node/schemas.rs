@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::super::schema::{join, CheckSchema, SchemaCheck};
+
 /// Node represents a deserialized node body from an API response.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Node {
@@ -21,6 +23,11 @@ pub struct Node {
 
     // Nodegroup identifier.
     pub nodegroup_id: String,
+
+    // Fields of the response that aren't modelled by this struct.
+    // They are kept as is when the struct is serialized back.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// NodeRoot represents a root of a deserialized node.
@@ -28,3 +35,15 @@ pub struct Node {
 pub struct NodeRoot {
     pub node: Node,
 }
+
+impl CheckSchema for Node {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck) {
+        check.extra(field, &self.extra);
+    }
+}
+
+impl CheckSchema for NodeRoot {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck) {
+        self.node.check_schema(&join(field, "node"), check);
+    }
+}
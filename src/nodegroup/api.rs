@@ -1,101 +1,264 @@
 use hyper::Method;
 
 use super::super::error::Error;
-use super::super::resource_url::{API_VERSION, CLUSTERS, NODEGROUPS, RESIZE};
+use super::super::resource_url::{expand, NODEGROUPS_PATH, NODEGROUP_PATH, NODEGROUP_RESIZE_PATH};
+use super::super::response::Response;
 use super::super::Client;
 use super::schemas;
 
-pub fn get(
-    client: &Client,
-    cluster_id: &str,
-    nodegroup_id: &str,
-) -> Result<schemas::Nodegroup, Error> {
-    let path = format!(
-        "/{}/{}/{}/{}/{}",
-        API_VERSION, CLUSTERS, cluster_id, NODEGROUPS, nodegroup_id
-    );
-    let req = client.new_request(Method::GET, &path, None)?;
-    let body = client.do_request(req)?;
-
-    let deserialized: schemas::NodegroupRoot =
-        serde_json::from_str(&body).map_err(|err| Error::DeserializeError(err, body))?;
-
-    Ok(deserialized.nodegroup)
-}
+api_call! {
+    name = "mks.nodegroup.get",
+    skip(client),
+    pub async fn get(
+        client: &Client,
+        cluster_id: &str,
+        nodegroup_id: &str,
+    ) -> Result<Response<schemas::Nodegroup>, Error> {
+        let path = expand(NODEGROUP_PATH, &[cluster_id, nodegroup_id])?;
+        let req = client.new_request(Method::GET, &path, None)?;
+        let deserialized: Response<schemas::NodegroupRoot> =
+            client.do_request_json(req, NODEGROUP_PATH).await?;
 
-pub fn list(client: &Client, cluster_id: &str) -> Result<Vec<schemas::Nodegroup>, Error> {
-    let path = format!(
-        "/{}/{}/{}/{}",
-        API_VERSION, CLUSTERS, cluster_id, NODEGROUPS
-    );
-    let req = client.new_request(Method::GET, &path, None)?;
-    let body = client.do_request(req)?;
+        Ok(deserialized.map(|root| root.nodegroup))
+    }
+}
 
-    let deserialized: schemas::ListRoot =
-        serde_json::from_str(&body).map_err(|err| Error::DeserializeError(err, body))?;
+api_call! {
+    name = "mks.nodegroup.list",
+    skip(client),
+    pub async fn list(
+        client: &Client,
+        cluster_id: &str,
+    ) -> Result<Response<Vec<schemas::Nodegroup>>, Error> {
+        let path = expand(NODEGROUPS_PATH, &[cluster_id])?;
+        let req = client.new_request(Method::GET, &path, None)?;
+        let deserialized: Response<schemas::ListRoot> =
+            client.do_request_json(req, NODEGROUPS_PATH).await?;
 
-    Ok(deserialized.nodegroups)
+        Ok(deserialized.map(|root| root.nodegroups))
+    }
 }
 
-pub fn create(client: &Client, cluster_id: &str, opts: &schemas::CreateOpts) -> Result<(), Error> {
-    let root_opts = schemas::CreateOptsRoot { nodegroup: opts };
-    let serialized = serde_json::to_string(&root_opts).map_err(Error::SerializeError)?;
+api_call! {
+    name = "mks.nodegroup.create",
+    skip(client, opts),
+    pub async fn create(
+        client: &Client,
+        cluster_id: &str,
+        opts: &schemas::CreateOpts,
+    ) -> Result<Response<()>, Error> {
+        let root_opts = schemas::CreateOptsRoot { nodegroup: opts };
+        let serialized = serde_json::to_string(&root_opts).map_err(Error::SerializeError)?;
 
-    let path = format!(
-        "/{}/{}/{}/{}",
-        API_VERSION, CLUSTERS, cluster_id, NODEGROUPS
-    );
-    let req = client.new_request(Method::POST, &path, Some(serialized))?;
-    client.do_request(req)?;
+        let path = expand(NODEGROUPS_PATH, &[cluster_id])?;
+        let req = client.new_request(Method::POST, &path, Some(serialized))?;
+        let resp = client.do_request(req, NODEGROUPS_PATH).await?;
 
-    Ok(())
+        Ok(resp.map(|_| ()))
+    }
 }
 
-pub fn delete(client: &Client, cluster_id: &str, nodegroup_id: &str) -> Result<(), Error> {
-    let path = format!(
-        "/{}/{}/{}/{}/{}",
-        API_VERSION, CLUSTERS, cluster_id, NODEGROUPS, nodegroup_id
-    );
-    let req = client.new_request(Method::DELETE, &path, None)?;
-    client.do_request(req)?;
+api_call! {
+    name = "mks.nodegroup.delete",
+    skip(client),
+    pub async fn delete(
+        client: &Client,
+        cluster_id: &str,
+        nodegroup_id: &str,
+    ) -> Result<Response<()>, Error> {
+        let path = expand(NODEGROUP_PATH, &[cluster_id, nodegroup_id])?;
+        let req = client.new_request(Method::DELETE, &path, None)?;
+        let resp = client.do_request(req, NODEGROUP_PATH).await?;
 
-    Ok(())
+        Ok(resp.map(|_| ()))
+    }
 }
 
-pub fn resize(
-    client: &Client,
-    cluster_id: &str,
-    nodegroup_id: &str,
-    opts: &schemas::ResizeOpts,
-) -> Result<(), Error> {
-    let root_opts = schemas::ResizeOptsRoot { nodegroup: opts };
-    let serialized = serde_json::to_string(&root_opts).map_err(Error::SerializeError)?;
-
-    let path = format!(
-        "/{}/{}/{}/{}/{}/{}",
-        API_VERSION, CLUSTERS, cluster_id, NODEGROUPS, nodegroup_id, RESIZE
-    );
-    let req = client.new_request(Method::POST, &path, Some(serialized))?;
-    client.do_request(req)?;
-
-    Ok(())
+api_call! {
+    name = "mks.nodegroup.resize",
+    skip(client, opts),
+    pub async fn resize(
+        client: &Client,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        opts: &schemas::ResizeOpts,
+    ) -> Result<Response<()>, Error> {
+        let root_opts = schemas::ResizeOptsRoot { nodegroup: opts };
+        let serialized = serde_json::to_string(&root_opts).map_err(Error::SerializeError)?;
+
+        let path = expand(NODEGROUP_RESIZE_PATH, &[cluster_id, nodegroup_id])?;
+        let req = client.new_request(Method::POST, &path, Some(serialized))?;
+        let resp = client.do_request(req, NODEGROUP_RESIZE_PATH).await?;
+
+        Ok(resp.map(|_| ()))
+    }
+}
+
+api_call! {
+    name = "mks.nodegroup.update",
+    skip(client, opts),
+    pub async fn update(
+        client: &Client,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        opts: &schemas::UpdateOpts,
+    ) -> Result<Response<()>, Error> {
+        let root_opts = schemas::UpdateOptsRoot { nodegroup: opts };
+        let serialized = serde_json::to_string(&root_opts).map_err(Error::SerializeError)?;
+
+        let path = expand(NODEGROUP_PATH, &[cluster_id, nodegroup_id])?;
+        let req = client.new_request(Method::PUT, &path, Some(serialized))?;
+        let resp = client.do_request(req, NODEGROUP_PATH).await?;
+
+        Ok(resp.map(|_| ()))
+    }
 }
 
-pub fn update(
-    client: &Client,
-    cluster_id: &str,
-    nodegroup_id: &str,
-    opts: &schemas::UpdateOpts,
-) -> Result<(), Error> {
-    let root_opts = schemas::UpdateOptsRoot { nodegroup: opts };
-    let serialized = serde_json::to_string(&root_opts).map_err(Error::SerializeError)?;
-
-    let path = format!(
-        "/{}/{}/{}/{}/{}",
-        API_VERSION, CLUSTERS, cluster_id, NODEGROUPS, nodegroup_id
-    );
-    let req = client.new_request(Method::PUT, &path, Some(serialized))?;
-    client.do_request(req)?;
-
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::InMemoryTransport;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    const NODEGROUP: &str = r#"{
+        "id": "a376745a-fbcb-413d-b418-169d059d79ce",
+        "created_at": "2020-02-19T15:31:49.710818Z",
+        "updated_at": null,
+        "cluster_id": "79265d1a-b2a5-4ec6-bd2f-b0e1b4283562",
+        "flavor_id": "99b62bd8-7d2c-4d5c-8b6d-3bb4f8cd9c43",
+        "volume_gb": 10,
+        "volume_type": "fast.ru-3a",
+        "local_volume": false,
+        "availability_zone": "ru-3a",
+        "nodes": [
+            {
+                "id": "6d2e8c5d-7fb4-4d9a-b0a9-1e7a0f1f3c1b",
+                "created_at": "2020-02-19T15:31:49.710818Z",
+                "updated_at": null,
+                "hostname": "test-node-1",
+                "ip": "198.51.100.11",
+                "nodegroup_id": "a376745a-fbcb-413d-b418-169d059d79ce",
+                "taints": []
+            }
+        ],
+        "labels": {
+            "test-label-key": "test-label-value"
+        }
+    }"#;
+
+    fn client(transport: &Arc<InMemoryTransport>) -> Client {
+        Client::builder()
+            .with_transport(transport.clone())
+            .build("https://example.org", "token_a")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_nodegroup() {
+        let body = format!(r#"{{"nodegroup": {}}}"#, NODEGROUP);
+        let transport = Arc::new(InMemoryTransport::new().with_response(200, &body));
+
+        let nodegroup = get(&client(&transport), "79265d1a", "a376745a")
+            .await
+            .unwrap()
+            .into_body();
+
+        assert_eq!(nodegroup.id, "a376745a-fbcb-413d-b418-169d059d79ce");
+        assert_eq!(nodegroup.volume_gb, 10);
+        assert_eq!(nodegroup.nodes.len(), 1);
+        assert_eq!(nodegroup.nodes[0].hostname, "test-node-1");
+        assert_eq!(nodegroup.labels["test-label-key"], "test-label-value");
+        assert!(nodegroup.extra.is_empty());
+        assert_eq!(nodegroup.nodes[0].extra["taints"], serde_json::json!([]));
+        assert_eq!(
+            transport.requests()[0].uri,
+            "https://example.org/v1/clusters/79265d1a/nodegroups/a376745a"
+        );
+    }
+
+    #[tokio::test]
+    async fn list_nodegroups() {
+        let body = format!(r#"{{"nodegroups": [{}]}}"#, NODEGROUP);
+        let transport = Arc::new(InMemoryTransport::new().with_response(200, &body));
+
+        let nodegroups = list(&client(&transport), "79265d1a")
+            .await
+            .unwrap()
+            .into_body();
+
+        assert_eq!(nodegroups.len(), 1);
+        assert_eq!(
+            transport.requests()[0].uri,
+            "https://example.org/v1/clusters/79265d1a/nodegroups"
+        );
+    }
+
+    #[tokio::test]
+    async fn create_nodegroup() {
+        let transport = Arc::new(InMemoryTransport::new().with_response(204, ""));
+        let opts = schemas::CreateOpts::new(2, false, "ru-3a")
+            .with_cpus(1)
+            .with_ram_mb(1024);
+
+        create(&client(&transport), "79265d1a", &opts)
+            .await
+            .unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests[0].method, Method::POST);
+        let sent: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(sent["nodegroup"]["count"], 2);
+        assert_eq!(sent["nodegroup"]["cpus"], 1);
+        assert_eq!(sent["nodegroup"]["availability_zone"], "ru-3a");
+    }
+
+    #[tokio::test]
+    async fn resize_nodegroup() {
+        let transport = Arc::new(InMemoryTransport::new().with_response(204, ""));
+
+        resize(
+            &client(&transport),
+            "79265d1a",
+            "a376745a",
+            &schemas::ResizeOpts::new(3),
+        )
+        .await
+        .unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(
+            requests[0].uri,
+            "https://example.org/v1/clusters/79265d1a/nodegroups/a376745a/resize"
+        );
+        assert_eq!(requests[0].body, r#"{"nodegroup":{"desired":3}}"#);
+    }
+
+    #[tokio::test]
+    async fn update_nodegroup() {
+        let transport = Arc::new(InMemoryTransport::new().with_response(204, ""));
+        let mut labels = HashMap::new();
+        labels.insert(String::from("a"), String::from("b"));
+        let opts = schemas::UpdateOpts::new().with_labels(labels);
+
+        update(&client(&transport), "79265d1a", "a376745a", &opts)
+            .await
+            .unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests[0].method, Method::PUT);
+        assert_eq!(requests[0].body, r#"{"nodegroup":{"labels":{"a":"b"}}}"#);
+    }
+
+    #[tokio::test]
+    async fn delete_nodegroup() {
+        let transport = Arc::new(InMemoryTransport::new().with_response(204, ""));
+
+        delete(&client(&transport), "79265d1a", "a376745a")
+            .await
+            .unwrap();
+
+        assert_eq!(transport.requests()[0].method, Method::DELETE);
+    }
 }
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::super::node::schemas::Node;
+use super::super::schema::{join, CheckSchema, SchemaCheck};
 
 /// Nodegroup represents a deserialized nodegroup body from an API response.
 #[derive(Debug, Deserialize, Serialize)]
@@ -39,6 +40,11 @@ pub struct Nodegroup {
 
     /// A map of user-defined Kubernetes labels for each node in the group.
     pub labels: HashMap<String, String>,
+
+    /// Fields of the response that aren't modelled by this struct.
+    /// They are kept as is when the struct is serialized back.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// NodegroupRoot represents a root of a deserialized nodegroup.
@@ -193,3 +199,24 @@ impl Default for UpdateOpts {
 pub struct UpdateOptsRoot<'a> {
     pub nodegroup: &'a UpdateOpts,
 }
+
+impl CheckSchema for Nodegroup {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck) {
+        check.extra(field, &self.extra);
+        self.nodes.check_schema(&join(field, "nodes"), check);
+    }
+}
+
+impl CheckSchema for NodegroupRoot {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck) {
+        self.nodegroup
+            .check_schema(&join(field, "nodegroup"), check);
+    }
+}
+
+impl CheckSchema for ListRoot {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck) {
+        self.nodegroups
+            .check_schema(&join(field, "nodegroups"), check);
+    }
+}
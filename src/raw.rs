@@ -0,0 +1,94 @@
+use hyper::body::Bytes;
+use hyper::Method;
+use serde::Serialize;
+
+use super::error::Error;
+use super::response::Response;
+use super::Client;
+
+// Path template of custom requests in spans and metrics.
+// Real paths contain resource identifiers so they aren't recorded.
+const CUSTOM_ENDPOINT: &str = "custom";
+
+api_call! {
+    name = "mks.request",
+    skip(client, body),
+    pub async fn request<B: Serialize>(
+        client: &Client,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<Response<Bytes>, Error> {
+        let serialized = match body {
+            Some(body) => Some(serde_json::to_string(body).map_err(Error::SerializeError)?),
+            None => None,
+        };
+
+        let req = client.new_request(method, path, serialized)?;
+        client.do_request(req, CUSTOM_ENDPOINT).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::InMemoryTransport;
+    use std::sync::Arc;
+
+    fn client(transport: &Arc<InMemoryTransport>) -> Client {
+        Client::builder()
+            .with_transport(transport.clone())
+            .build("https://example.org/mks", "token_a")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn custom_request() {
+        let transport = Arc::new(
+            InMemoryTransport::new().with_response(200, r#"{"cluster": {"name": "renamed"}}"#),
+        );
+        let opts = serde_json::json!({"cluster": {"name": "renamed"}});
+
+        let resp: serde_json::Value = client(&transport)
+            .request(Method::PUT, "/v1/clusters/dbe7559b", Some(&opts))
+            .await
+            .unwrap();
+
+        assert_eq!(resp["cluster"]["name"], "renamed");
+        let requests = transport.requests();
+        assert_eq!(requests[0].method, Method::PUT);
+        assert_eq!(
+            requests[0].uri,
+            "https://example.org/mks/v1/clusters/dbe7559b"
+        );
+        assert_eq!(requests[0].body, r#"{"cluster":{"name":"renamed"}}"#);
+    }
+
+    #[tokio::test]
+    async fn raw_request() {
+        let kubeconfig = "apiVersion: v1\nkind: Config\n";
+        let transport = Arc::new(InMemoryTransport::new().with_response(200, kubeconfig));
+
+        let body = client(&transport)
+            .request_raw(Method::GET, "/v1/clusters/dbe7559b/kubeconfig", None::<&()>)
+            .await
+            .unwrap();
+
+        assert_eq!(body, kubeconfig.as_bytes());
+        assert!(transport.requests()[0].body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn request_to_another_host() {
+        let transport = Arc::new(InMemoryTransport::new());
+
+        for path in &["https://attacker.org/x", "//attacker.org/x"] {
+            let err = client(&transport)
+                .request_raw(Method::GET, path, None::<&()>)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::PathError(_)));
+        }
+        assert!(transport.requests().is_empty());
+    }
+}
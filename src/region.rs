@@ -0,0 +1,110 @@
+use std::str::FromStr;
+
+use super::error::Error;
+
+/// Region represents a region where the MKS API is available.
+///
+/// Regions that aren't known by this library are kept in the `Other` variant,
+/// their endpoints must be set with `Builder::with_region_endpoint`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Region {
+    Ru1,
+    Ru2,
+    Ru3,
+    Ru7,
+    Ru8,
+    Other(String),
+}
+
+impl Region {
+    /// Get all known regions.
+    pub fn all() -> &'static [Region] {
+        &[
+            Region::Ru1,
+            Region::Ru2,
+            Region::Ru3,
+            Region::Ru7,
+            Region::Ru8,
+        ]
+    }
+
+    /// Get region name, for example "ru-1".
+    pub fn as_str(&self) -> &str {
+        match self {
+            Region::Ru1 => "ru-1",
+            Region::Ru2 => "ru-2",
+            Region::Ru3 => "ru-3",
+            Region::Ru7 => "ru-7",
+            Region::Ru8 => "ru-8",
+            Region::Other(name) => name,
+        }
+    }
+
+    /// Get public MKS endpoint of the region, for example "https://ru-1.mks.selcloud.ru".
+    ///
+    /// Endpoints of `Other` regions aren't known, so `None` is returned for them.
+    pub fn endpoint(&self) -> Option<String> {
+        match self {
+            Region::Other(_) => None,
+            region => Some(format!("https://{}.mks.selcloud.ru", region.as_str())),
+        }
+    }
+}
+
+impl std::fmt::Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl FromStr for Region {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        if let Some(region) = Region::all().iter().find(|region| region.as_str() == name) {
+            return Ok(region.clone());
+        }
+
+        // Name of an unknown region is a part of its endpoint host.
+        let is_valid = |c: char| c.is_ascii_alphanumeric() || c == '-';
+        if name.is_empty() || !name.chars().all(is_valid) {
+            return Err(Error::RegionError(String::from(s)));
+        }
+
+        Ok(Region::Other(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_regions() {
+        for region in Region::all() {
+            assert_eq!(region.to_string().parse::<Region>().unwrap(), *region);
+        }
+        assert_eq!("RU-3".parse::<Region>().unwrap(), Region::Ru3);
+        assert_eq!(
+            "ru-9".parse::<Region>().unwrap(),
+            Region::Other(String::from("ru-9"))
+        );
+        for name in &["", "ru 9", "evil.org/ru"] {
+            assert!(matches!(name.parse::<Region>(), Err(Error::RegionError(_))));
+        }
+    }
+
+    #[test]
+    fn region_endpoints() {
+        assert_eq!(
+            Region::Ru1.endpoint().as_deref(),
+            Some("https://ru-1.mks.selcloud.ru")
+        );
+        assert_eq!(
+            Region::Ru8.endpoint().as_deref(),
+            Some("https://ru-8.mks.selcloud.ru")
+        );
+        assert_eq!(Region::Other(String::from("ru-9")).endpoint(), None);
+    }
+}
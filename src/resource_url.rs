@@ -1,18 +1,198 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+use super::error::Error;
+
+// Path segments are macros so they can be joined into path templates with `concat!`.
+macro_rules! segment {
+    (api_version) => {
+        "v1"
+    };
+    (clusters) => {
+        "clusters"
+    };
+    (kubeversions) => {
+        "kubeversions"
+    };
+    (nodegroups) => {
+        "nodegroups"
+    };
+    (resize) => {
+        "resize"
+    };
+    (reinstall) => {
+        "reinstall"
+    };
+    (tasks) => {
+        "tasks"
+    };
+    ($placeholder:literal) => {
+        $placeholder
+    };
+}
+
+// Join segments into a path template, for example "/v1/clusters/{cluster_id}".
+macro_rules! template {
+    ($($segment:tt),+) => {
+        concat!($("/", segment!($segment)),+)
+    };
+}
+
 /// Supported API version.
-pub const API_VERSION: &str = "v1";
+pub const API_VERSION: &str = segment!(api_version);
 
 /// Clusters resource URL.
-pub const CLUSTERS: &str = "clusters";
+pub const CLUSTERS: &str = segment!(clusters);
 
 /// Kubeversions resource URL.
-pub const KUBEVERSIONS: &str = "kubeversions";
+pub const KUBEVERSIONS: &str = segment!(kubeversions);
 
 /// Nodegroups resource URLs.
-pub const NODEGROUPS: &str = "nodegroups";
-pub const RESIZE: &str = "resize";
+pub const NODEGROUPS: &str = segment!(nodegroups);
+pub const RESIZE: &str = segment!(resize);
 
 /// Nodes resource URLs.
-pub const REINSTALL: &str = "reinstall";
+pub const REINSTALL: &str = segment!(reinstall);
 
 /// Tasks resource URLs.
-pub const TASKS: &str = "tasks";
+pub const TASKS: &str = segment!(tasks);
+
+/// Keystone tokens resource URL.
+pub const AUTH_TOKENS: &str = "auth/tokens";
+
+// Path templates of the API requests that are recorded in spans and metrics.
+pub(crate) const CLUSTERS_PATH: &str = template!(api_version, clusters);
+pub(crate) const CLUSTER_PATH: &str = template!(api_version, clusters, "{cluster_id}");
+pub(crate) const KUBEVERSIONS_PATH: &str = template!(api_version, kubeversions);
+pub(crate) const NODEGROUPS_PATH: &str =
+    template!(api_version, clusters, "{cluster_id}", nodegroups);
+pub(crate) const NODEGROUP_PATH: &str = template!(
+    api_version,
+    clusters,
+    "{cluster_id}",
+    nodegroups,
+    "{nodegroup_id}"
+);
+pub(crate) const NODEGROUP_RESIZE_PATH: &str = template!(
+    api_version,
+    clusters,
+    "{cluster_id}",
+    nodegroups,
+    "{nodegroup_id}",
+    resize
+);
+pub(crate) const NODE_PATH: &str = template!(
+    api_version,
+    clusters,
+    "{cluster_id}",
+    nodegroups,
+    "{nodegroup_id}",
+    "{node_id}"
+);
+pub(crate) const NODE_REINSTALL_PATH: &str = template!(
+    api_version,
+    clusters,
+    "{cluster_id}",
+    nodegroups,
+    "{nodegroup_id}",
+    "{node_id}",
+    reinstall
+);
+pub(crate) const TASKS_PATH: &str = template!(api_version, clusters, "{cluster_id}", tasks);
+pub(crate) const TASK_PATH: &str =
+    template!(api_version, clusters, "{cluster_id}", tasks, "{task_id}");
+
+// Characters that are percent-encoded in identifiers so each of them stays a single path segment.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+// Build request path by substituting identifiers into placeholders of the template in order.
+pub(crate) fn expand(template: &str, ids: &[&str]) -> Result<String, Error> {
+    let placeholders = template
+        .split('/')
+        .filter(|segment| segment.starts_with('{'))
+        .count();
+    if placeholders != ids.len() {
+        return Err(Error::PathError(format!(
+            "{} expects {} identifiers, got {}",
+            template,
+            placeholders,
+            ids.len()
+        )));
+    }
+
+    let mut ids = ids.iter();
+    let mut segments = Vec::new();
+    for segment in template.split('/') {
+        if !segment.starts_with('{') {
+            segments.push(segment.to_string());
+            continue;
+        }
+
+        let id = ids.next().copied().unwrap_or_default();
+        if id.is_empty() || id == "." || id == ".." {
+            return Err(Error::PathError(format!(
+                "bad identifier {:?} for {}",
+                id, segment
+            )));
+        }
+        segments.push(utf8_percent_encode(id, PATH_SEGMENT).to_string());
+    }
+
+    Ok(segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_templates() {
+        assert_eq!(CLUSTER_PATH, "/v1/clusters/{cluster_id}");
+        assert_eq!(
+            NODE_REINSTALL_PATH,
+            "/v1/clusters/{cluster_id}/nodegroups/{nodegroup_id}/{node_id}/reinstall"
+        );
+        assert_eq!(
+            TASKS_PATH,
+            format!("/{}/{}/{{cluster_id}}/{}", API_VERSION, CLUSTERS, TASKS)
+        );
+    }
+
+    #[test]
+    fn expand_templates() {
+        assert_eq!(expand(KUBEVERSIONS_PATH, &[]).unwrap(), "/v1/kubeversions");
+        assert_eq!(
+            expand(NODEGROUP_RESIZE_PATH, &["cluster_a", "nodegroup_a"]).unwrap(),
+            "/v1/clusters/cluster_a/nodegroups/nodegroup_a/resize"
+        );
+        assert_eq!(
+            expand(TASK_PATH, &["cluster_a", "../../x?y#z"]).unwrap(),
+            "/v1/clusters/cluster_a/tasks/..%2F..%2Fx%3Fy%23z"
+        );
+    }
+
+    #[test]
+    fn expand_bad_identifiers() {
+        for ids in &[
+            &[][..],
+            &["cluster_a", "task_a"][..],
+            &[".."][..],
+            &[""][..],
+        ] {
+            assert!(matches!(
+                expand(CLUSTER_PATH, ids),
+                Err(Error::PathError(_))
+            ));
+        }
+    }
+}
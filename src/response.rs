@@ -0,0 +1,300 @@
+use hyper::body::Bytes;
+use hyper::{HeaderMap, Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::error::{Error, REQUEST_ID_HEADER};
+use super::{cluster, kubeversion, node, nodegroup, raw, task, Client};
+
+/// `Response` contains a response body with the HTTP metadata of the response.
+#[derive(Debug)]
+pub struct Response<T> {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: T,
+}
+
+impl<T> Response<T> {
+    pub(crate) fn new(status: StatusCode, headers: HeaderMap, body: T) -> Response<T> {
+        Response {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    /// Get HTTP status code of the response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Get HTTP headers of the response.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Get server request identifier from the `x-request-id` header.
+    ///
+    /// It's useful for support tickets about failed operations.
+    pub fn request_id(&self) -> Option<&str> {
+        self.headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+    }
+
+    /// Get response body.
+    pub fn body(&self) -> &T {
+        &self.body
+    }
+
+    /// Get response body and drop the metadata.
+    pub fn into_body(self) -> T {
+        self.body
+    }
+
+    /// Convert response body keeping the metadata.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Response<U> {
+        Response {
+            status: self.status,
+            headers: self.headers,
+            body: f(self.body),
+        }
+    }
+}
+
+impl Response<Bytes> {
+    // Deserialize JSON response body.
+    pub(crate) fn json<R: DeserializeOwned>(self) -> Result<Response<R>, Error> {
+        let body: R = serde_json::from_slice(&self.body).map_err(|err| {
+            Error::DeserializeError(err, String::from_utf8_lossy(&self.body).into_owned())
+        })?;
+
+        Ok(Response {
+            status: self.status,
+            headers: self.headers,
+            body,
+        })
+    }
+}
+
+/// `MetadataClient` makes the same calls as `Client` but returns responses with metadata.
+///
+/// Use `Client::with_metadata` to get it.
+pub struct MetadataClient<'a> {
+    client: &'a Client,
+}
+
+impl<'a> MetadataClient<'a> {
+    pub(crate) fn new(client: &'a Client) -> MetadataClient<'a> {
+        MetadataClient { client }
+    }
+}
+
+/// Methods to work with clusters.
+impl MetadataClient<'_> {
+    /// Get a cluster.
+    pub async fn get_cluster(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Response<cluster::schemas::Cluster>, Error> {
+        cluster::api::get(self.client, cluster_id).await
+    }
+
+    /// List clusters.
+    pub async fn list_clusters(&self) -> Result<Response<Vec<cluster::schemas::Cluster>>, Error> {
+        cluster::api::list(self.client).await
+    }
+
+    /// Create a cluster.
+    pub async fn create_cluster(
+        &self,
+        opts: &cluster::schemas::CreateOpts,
+    ) -> Result<Response<cluster::schemas::Cluster>, Error> {
+        cluster::api::create(self.client, opts).await
+    }
+
+    /// Delete a cluster.
+    pub async fn delete_cluster(&self, cluster_id: &str) -> Result<Response<()>, Error> {
+        cluster::api::delete(self.client, cluster_id).await
+    }
+}
+
+/// Methods to work with Kubernetes versions.
+impl MetadataClient<'_> {
+    /// List all Kubernetes versions.
+    pub async fn list_kube_versions(
+        &self,
+    ) -> Result<Response<Vec<kubeversion::schemas::KubeVersion>>, Error> {
+        kubeversion::api::list(self.client).await
+    }
+}
+
+/// Methods to work with nodes.
+impl MetadataClient<'_> {
+    /// Get a cluster node.
+    pub async fn get_node(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        node_id: &str,
+    ) -> Result<Response<node::schemas::Node>, Error> {
+        node::api::get(self.client, cluster_id, nodegroup_id, node_id).await
+    }
+
+    /// Reinstall a cluster node.
+    pub async fn reinstall_node(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        node_id: &str,
+    ) -> Result<Response<()>, Error> {
+        node::api::reinstall(self.client, cluster_id, nodegroup_id, node_id).await
+    }
+}
+
+/// Methods to work with nodegroups.
+impl MetadataClient<'_> {
+    /// Get a cluster nodegroup.
+    pub async fn get_nodegroup(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+    ) -> Result<Response<nodegroup::schemas::Nodegroup>, Error> {
+        nodegroup::api::get(self.client, cluster_id, nodegroup_id).await
+    }
+
+    /// List cluster nodegroups.
+    pub async fn list_nodegroups(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Response<Vec<nodegroup::schemas::Nodegroup>>, Error> {
+        nodegroup::api::list(self.client, cluster_id).await
+    }
+
+    /// Create a cluster nodegroup.
+    pub async fn create_nodegroup(
+        &self,
+        cluster_id: &str,
+        opts: &nodegroup::schemas::CreateOpts,
+    ) -> Result<Response<()>, Error> {
+        nodegroup::api::create(self.client, cluster_id, opts).await
+    }
+
+    /// Delete a cluster nodegroup.
+    pub async fn delete_nodegroup(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+    ) -> Result<Response<()>, Error> {
+        nodegroup::api::delete(self.client, cluster_id, nodegroup_id).await
+    }
+
+    /// Resize a cluster nodegroup.
+    pub async fn resize_nodegroup(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        opts: &nodegroup::schemas::ResizeOpts,
+    ) -> Result<Response<()>, Error> {
+        nodegroup::api::resize(self.client, cluster_id, nodegroup_id, opts).await
+    }
+
+    /// Update a cluster nodegroup.
+    pub async fn update_nodegroup(
+        &self,
+        cluster_id: &str,
+        nodegroup_id: &str,
+        opts: &nodegroup::schemas::UpdateOpts,
+    ) -> Result<Response<()>, Error> {
+        nodegroup::api::update(self.client, cluster_id, nodegroup_id, opts).await
+    }
+}
+
+/// Methods to work with tasks.
+impl MetadataClient<'_> {
+    /// Get a task.
+    pub async fn get_task(
+        &self,
+        cluster_id: &str,
+        task_id: &str,
+    ) -> Result<Response<task::schemas::Task>, Error> {
+        task::api::get(self.client, cluster_id, task_id).await
+    }
+
+    /// List tasks.
+    pub async fn list_tasks(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Response<Vec<task::schemas::Task>>, Error> {
+        task::api::list(self.client, cluster_id).await
+    }
+}
+
+/// Methods to send requests that aren't wrapped by the client yet.
+impl MetadataClient<'_> {
+    /// Send a request to the MKS API and deserialize the JSON response body.
+    ///
+    /// See `Client::request` for details.
+    pub async fn request<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<Response<T>, Error> {
+        raw::request(self.client, method, path, body).await?.json()
+    }
+
+    /// Send a request to the MKS API and get the raw response body.
+    ///
+    /// See `Client::request` for details.
+    pub async fn request_raw<B: Serialize>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<Response<Bytes>, Error> {
+        raw::request(self.client, method, path, body).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::InMemoryTransport;
+    use hyper::header::HeaderValue;
+
+    #[tokio::test]
+    async fn response_with_metadata() {
+        let body = r#"{"kube_versions": [{"version": "1.17.9", "is_default": true}]}"#;
+        let transport = InMemoryTransport::new().with_http_response(
+            hyper::Response::builder()
+                .header("x-request-id", "req-1")
+                .body(hyper::body::Bytes::from(body))
+                .unwrap(),
+        );
+        let client = Client::builder()
+            .with_transport(transport)
+            .build("https://example.org", "token_a")
+            .unwrap();
+
+        let resp = client.with_metadata().list_kube_versions().await.unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.request_id(), Some("req-1"));
+        assert_eq!(
+            resp.headers()["x-request-id"],
+            HeaderValue::from_static("req-1")
+        );
+        assert_eq!(resp.body().len(), 1);
+        assert_eq!(resp.into_body()[0].version, "1.17.9");
+    }
+
+    #[test]
+    fn bad_json_body() {
+        let resp = Response::new(StatusCode::OK, HeaderMap::new(), Bytes::from("{"));
+
+        let err = resp.json::<serde_json::Value>().unwrap_err();
+
+        assert!(matches!(err, Error::DeserializeError(_, body) if body == "{"));
+    }
+}
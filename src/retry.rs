@@ -0,0 +1,242 @@
+use chrono::{DateTime, Utc};
+use hyper::header::{HeaderMap, RETRY_AFTER};
+use hyper::{Method, StatusCode};
+use rand::Rng;
+use std::time::Duration;
+
+/// Header that marks a mutating request as safe to be retried.
+pub const IDEMPOTENCY_KEY: &str = "idempotency-key";
+
+// Default retry policy parameters.
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_INITIAL_BACKOFF_MS: u64 = 500;
+const DEFAULT_MAX_BACKOFF_SECS: u64 = 30;
+const DEFAULT_MULTIPLIER: u32 = 2;
+
+/// `RetryPolicy` configures automatic retries of failed requests.
+///
+/// Connection errors, timeouts, 429 and 5xx responses are retried with
+/// exponential backoff. A `Retry-After` response header is honoured when present,
+/// but the delay never exceeds the maximum backoff.
+///
+/// Only safe and idempotent requests (GET, PUT, DELETE, etc.) are retried.
+/// Other requests are retried only if they carry the `Idempotency-Key` header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: u32,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: Duration::from_millis(DEFAULT_INITIAL_BACKOFF_MS),
+            max_backoff: Duration::from_secs(DEFAULT_MAX_BACKOFF_SECS),
+            multiplier: DEFAULT_MULTIPLIER,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Construct the new RetryPolicy with default parameters.
+    ///
+    /// Default policy makes up to 3 retries starting with 500 ms backoff that is
+    /// doubled after each attempt up to 30 seconds, with jitter.
+    pub fn new() -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
+    /// Construct the new RetryPolicy that disables retries.
+    pub fn none() -> RetryPolicy {
+        RetryPolicy::default().with_max_retries(0)
+    }
+
+    /// Set maximum number of retries after the first attempt.
+    pub fn with_max_retries(mut self, max_retries: u32) -> RetryPolicy {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set backoff before the first retry.
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> RetryPolicy {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Set the upper limit for a single backoff.
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> RetryPolicy {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Set backoff multiplier that is applied after each retry.
+    pub fn with_multiplier(mut self, multiplier: u32) -> RetryPolicy {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Enable or disable random jitter of backoffs.
+    pub fn with_jitter(mut self, jitter: bool) -> RetryPolicy {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Get maximum number of retries after the first attempt.
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    // Check if the request with provided method and headers can be sent again.
+    pub(crate) fn allows(&self, method: &Method, headers: &HeaderMap) -> bool {
+        self.max_retries > 0 && (method.is_idempotent() || headers.contains_key(IDEMPOTENCY_KEY))
+    }
+
+    // Get backoff before the retry with provided number starting from zero.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.saturating_pow(retry);
+        let backoff = self
+            .initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+
+        if !self.jitter {
+            return backoff;
+        }
+
+        // Use "equal jitter": keep a half of the backoff and randomize the rest.
+        let half = backoff / 2;
+        half + half.mul_f64(rand::thread_rng().gen::<f64>())
+    }
+
+    // Get delay before the retry with provided number using the Retry-After value if it's present.
+    pub(crate) fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        match retry_after {
+            Some(retry_after) => retry_after.min(self.max_backoff),
+            None => self.backoff(retry),
+        }
+    }
+}
+
+// Check if response status code means that request can be retried.
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+// Parse Retry-After header that contains delay in seconds or HTTP date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.with_timezone(&Utc) - Utc::now();
+
+    Some(delay.to_std().unwrap_or_else(|_| Duration::from_secs(0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+
+    #[test]
+    fn default_policy() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(policy.max_retries(), DEFAULT_MAX_RETRIES);
+        assert!(policy.jitter);
+        assert_eq!(RetryPolicy::none().max_retries(), 0);
+    }
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = RetryPolicy::new()
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(1000))
+            .with_jitter(false);
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff(4), Duration::from_millis(1000));
+        assert_eq!(policy.backoff(100), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn jittered_backoff() {
+        let policy = RetryPolicy::new().with_initial_backoff(Duration::from_millis(100));
+
+        for _ in 0..100 {
+            let backoff = policy.backoff(1);
+            assert!(backoff >= Duration::from_millis(100));
+            assert!(backoff <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn capped_retry_after() {
+        let policy = RetryPolicy::new()
+            .with_max_backoff(Duration::from_secs(10))
+            .with_jitter(false);
+
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(86400))),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(2))),
+            Duration::from_secs(2)
+        );
+        assert_eq!(policy.delay(0, None), policy.backoff(0));
+    }
+
+    #[test]
+    fn allowed_methods() {
+        let policy = RetryPolicy::default();
+        let mut headers = HeaderMap::new();
+
+        assert!(policy.allows(&Method::GET, &headers));
+        assert!(policy.allows(&Method::PUT, &headers));
+        assert!(policy.allows(&Method::DELETE, &headers));
+        assert!(!policy.allows(&Method::POST, &headers));
+        assert!(!RetryPolicy::none().allows(&Method::GET, &headers));
+
+        headers.insert(IDEMPOTENCY_KEY, HeaderValue::from_static("key"));
+        assert!(policy.allows(&Method::POST, &headers));
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::CONFLICT));
+    }
+
+    #[test]
+    fn parse_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(0)));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+    }
+}
@@ -0,0 +1,219 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::error::Error;
+
+/// SchemaWarning describes a difference between a response and the schema of this library.
+///
+/// Responses are checked only in strict mode, see `Builder::with_strict_schemas`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaWarning {
+    /// Request path template, for example "/v1/clusters/{cluster_id}".
+    pub endpoint: &'static str,
+
+    /// Path of the field in the response body, for example "clusters[0].status".
+    pub field: String,
+
+    /// Kind of the difference.
+    pub kind: SchemaWarningKind,
+}
+
+/// SchemaWarningKind represents kinds of differences between a response and the schema.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SchemaWarningKind {
+    /// Field of the schema is missing in the response.
+    MissingField,
+
+    /// Field of the response isn't modelled by the schema.
+    UnexpectedField,
+
+    /// Enum field contains an unknown value.
+    UnknownEnumValue(String),
+}
+
+impl std::fmt::Display for SchemaWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            SchemaWarningKind::MissingField => {
+                write!(f, "{}: missing field {}", self.endpoint, self.field)
+            }
+            SchemaWarningKind::UnexpectedField => {
+                write!(f, "{}: unexpected field {}", self.endpoint, self.field)
+            }
+            SchemaWarningKind::UnknownEnumValue(value) => write!(
+                f,
+                "{}: unknown value {} of field {}",
+                self.endpoint, value, self.field
+            ),
+        }
+    }
+}
+
+/// `SchemaReporter` receives schema warnings of responses in strict mode.
+pub trait SchemaReporter: Send + Sync {
+    /// Report warnings of a single response.
+    fn report(&self, warnings: &[SchemaWarning]);
+}
+
+impl<R: SchemaReporter + ?Sized> SchemaReporter for std::sync::Arc<R> {
+    fn report(&self, warnings: &[SchemaWarning]) {
+        (**self).report(warnings)
+    }
+}
+
+// Collector of warnings for a single response.
+pub(crate) struct SchemaCheck {
+    endpoint: &'static str,
+    warnings: Vec<SchemaWarning>,
+}
+
+impl SchemaCheck {
+    // Report fields that are kept in a flattened map of unmodelled fields.
+    pub(crate) fn extra(&mut self, field: &str, extra: &Map<String, Value>) {
+        for key in extra.keys() {
+            self.warn(join(field, key), SchemaWarningKind::UnexpectedField);
+        }
+    }
+
+    // Report an unknown value of an enum field.
+    pub(crate) fn unknown_value(&mut self, field: &str, value: &str) {
+        self.warn(
+            String::from(field),
+            SchemaWarningKind::UnknownEnumValue(String::from(value)),
+        );
+    }
+
+    fn warn(&mut self, field: String, kind: SchemaWarningKind) {
+        self.warnings.push(SchemaWarning {
+            endpoint: self.endpoint,
+            field,
+            kind,
+        });
+    }
+
+    // Compare the raw response with the serialized schema struct.
+    // Fields that are missing in the response are serialized as nulls,
+    // fields that aren't modelled are dropped unless they are kept in a flattened map.
+    fn compare(&mut self, field: &str, raw: &Value, modelled: &Value) {
+        match (raw, modelled) {
+            (Value::Object(raw), Value::Object(modelled)) => {
+                for (key, value) in modelled {
+                    match raw.get(key) {
+                        Some(raw) => self.compare(&join(field, key), raw, value),
+                        None => self.warn(join(field, key), SchemaWarningKind::MissingField),
+                    }
+                }
+                for key in raw.keys().filter(|key| !modelled.contains_key(*key)) {
+                    self.warn(join(field, key), SchemaWarningKind::UnexpectedField);
+                }
+            }
+            (Value::Array(raw), Value::Array(modelled)) => {
+                for (i, (raw, value)) in raw.iter().zip(modelled).enumerate() {
+                    self.compare(&format!("{}[{}]", field, i), raw, value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// CheckSchema is implemented by response schemas that have fields
+// which can't be checked by comparing the response with the serialized struct.
+pub(crate) trait CheckSchema {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck);
+}
+
+impl<T: CheckSchema> CheckSchema for Vec<T> {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck) {
+        for (i, item) in self.iter().enumerate() {
+            item.check_schema(&format!("{}[{}]", field, i), check);
+        }
+    }
+}
+
+// Check the deserialized response against its raw body.
+pub(crate) fn check<R: CheckSchema + Serialize>(
+    endpoint: &'static str,
+    raw: &[u8],
+    deserialized: &R,
+) -> Result<Vec<SchemaWarning>, Error> {
+    let raw: Value = serde_json::from_slice(raw)
+        .map_err(|err| Error::DeserializeError(err, String::from_utf8_lossy(raw).into_owned()))?;
+    let modelled = serde_json::to_value(deserialized).map_err(Error::SerializeError)?;
+
+    let mut check = SchemaCheck {
+        endpoint,
+        warnings: Vec::new(),
+    };
+    check.compare("", &raw, &modelled);
+    deserialized.check_schema("", &mut check);
+
+    Ok(check.warnings)
+}
+
+// Get path of the nested field.
+pub(crate) fn join(field: &str, key: &str) -> String {
+    if field.is_empty() {
+        String::from(key)
+    } else {
+        format!("{}.{}", field, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cluster::schemas::{ListRoot, Status};
+    use crate::fixtures::CLUSTER;
+
+    const ENDPOINT: &str = "/v1/clusters";
+
+    fn warning(field: &str, kind: SchemaWarningKind) -> SchemaWarning {
+        SchemaWarning {
+            endpoint: ENDPOINT,
+            field: String::from(field),
+            kind,
+        }
+    }
+
+    #[test]
+    fn check_clusters() {
+        // Cluster without a modelled field, with unmodelled fields and an unknown status.
+        let cluster = CLUSTER
+            .replace(r#""updated_at": "2020-02-13T09:35:04.893559Z","#, "")
+            .replace(r#""status": "ACTIVE""#, r#""status": "PENDING_HIBERNATE""#)
+            .replace(
+                r#""enable_pod_security_policy": true"#,
+                r#""enable_pod_security_policy": true, "feature_gates": []"#,
+            )
+            .replacen("{", r#"{"hibernated": false,"#, 1);
+        let raw = format!(r#"{{"clusters": [{}]}}"#, cluster);
+        let deserialized: ListRoot = serde_json::from_str(&raw).unwrap();
+        assert_eq!(
+            deserialized.clusters[0].status,
+            Status::Other(String::from("PENDING_HIBERNATE"))
+        );
+
+        let warnings = check(ENDPOINT, raw.as_bytes(), &deserialized).unwrap();
+
+        assert_eq!(
+            warnings,
+            vec![
+                warning("clusters[0].updated_at", SchemaWarningKind::MissingField),
+                warning("clusters[0].hibernated", SchemaWarningKind::UnexpectedField),
+                warning(
+                    "clusters[0].kubernetes_options.feature_gates",
+                    SchemaWarningKind::UnexpectedField
+                ),
+                warning(
+                    "clusters[0].status",
+                    SchemaWarningKind::UnknownEnumValue(String::from("PENDING_HIBERNATE"))
+                ),
+            ]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "/v1/clusters: missing field clusters[0].updated_at"
+        );
+    }
+}
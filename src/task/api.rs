@@ -1,31 +1,122 @@
 use hyper::Method;
 
 use super::super::error::Error;
-use super::super::resource_url::{API_VERSION, CLUSTERS, TASKS};
+use super::super::resource_url::{expand, TASKS_PATH, TASK_PATH};
+use super::super::response::Response;
 use super::super::Client;
 use super::schemas;
 
-pub fn get(client: &Client, cluster_id: &str, task_id: &str) -> Result<schemas::Task, Error> {
-    let path = format!(
-        "/{}/{}/{}/{}/{}",
-        API_VERSION, CLUSTERS, cluster_id, TASKS, task_id
-    );
-    let req = client.new_request(Method::GET, &path, None)?;
-    let body = client.do_request(req)?;
+api_call! {
+    name = "mks.task.get",
+    skip(client),
+    pub async fn get(
+        client: &Client,
+        cluster_id: &str,
+        task_id: &str,
+    ) -> Result<Response<schemas::Task>, Error> {
+        let path = expand(TASK_PATH, &[cluster_id, task_id])?;
+        let req = client.new_request(Method::GET, &path, None)?;
+        let deserialized: Response<schemas::TaskRoot> =
+            client.do_request_json(req, TASK_PATH).await?;
 
-    let deserialized: schemas::TaskRoot =
-        serde_json::from_str(&body).map_err(|err| Error::DeserializeError(err, body))?;
+        Ok(deserialized.map(|root| root.task))
+    }
+}
+
+api_call! {
+    name = "mks.task.list",
+    skip(client),
+    pub async fn list(
+        client: &Client,
+        cluster_id: &str,
+    ) -> Result<Response<Vec<schemas::Task>>, Error> {
+        let path = expand(TASKS_PATH, &[cluster_id])?;
+        let req = client.new_request(Method::GET, &path, None)?;
+        let deserialized: Response<schemas::ListRoot> =
+            client.do_request_json(req, TASKS_PATH).await?;
 
-    Ok(deserialized.task)
+        Ok(deserialized.map(|root| root.tasks))
+    }
 }
 
-pub fn list(client: &Client, cluster_id: &str) -> Result<Vec<schemas::Task>, Error> {
-    let path = format!("/{}/{}/{}/{}", API_VERSION, CLUSTERS, cluster_id, TASKS);
-    let req = client.new_request(Method::GET, &path, None)?;
-    let body = client.do_request(req)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::InMemoryTransport;
+    use std::sync::Arc;
+
+    const TASK: &str = r#"{
+        "id": "2f0e3b5c-2fb1-4a9b-a2c4-c4a3a8d7e1f2",
+        "started_at": "2020-02-19T15:31:49.710818Z",
+        "updated_at": "2020-02-19T15:40:12.519874Z",
+        "cluster_id": "79265d1a-b2a5-4ec6-bd2f-b0e1b4283562",
+        "status": "DONE",
+        "type": "NODE_GROUP_RESIZE"
+    }"#;
+
+    fn client(transport: &Arc<InMemoryTransport>) -> Client {
+        Client::builder()
+            .with_transport(transport.clone())
+            .build("https://example.org", "token_a")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_task() {
+        let body = format!(r#"{{"task": {}}}"#, TASK);
+        let transport = Arc::new(InMemoryTransport::new().with_response(200, &body));
+
+        let task = get(&client(&transport), "79265d1a", "2f0e3b5c")
+            .await
+            .unwrap()
+            .into_body();
+
+        assert_eq!(task.id, "2f0e3b5c-2fb1-4a9b-a2c4-c4a3a8d7e1f2");
+        assert_eq!(task.status, schemas::Status::Done);
+        assert_eq!(task.task_type, schemas::Type::NodeGroupResize);
+        assert_eq!(
+            transport.requests()[0].uri,
+            "https://example.org/v1/clusters/79265d1a/tasks/2f0e3b5c"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_task_with_unknown_type() {
+        let body = format!(
+            r#"{{"task": {}}}"#,
+            TASK.replace("NODE_GROUP_RESIZE", "CLUSTER_HIBERNATE")
+        );
+        let transport = Arc::new(InMemoryTransport::new().with_response(200, &body));
+
+        let task = get(&client(&transport), "79265d1a", "2f0e3b5c")
+            .await
+            .unwrap()
+            .into_body();
+
+        assert_eq!(
+            task.task_type,
+            schemas::Type::Other(String::from("CLUSTER_HIBERNATE"))
+        );
+        assert_eq!(
+            serde_json::to_value(&task).unwrap()["type"],
+            "CLUSTER_HIBERNATE"
+        );
+    }
+
+    #[tokio::test]
+    async fn list_tasks() {
+        let body = format!(r#"{{"tasks": [{}]}}"#, TASK);
+        let transport = Arc::new(InMemoryTransport::new().with_response(200, &body));
 
-    let deserialized: schemas::ListRoot =
-        serde_json::from_str(&body).map_err(|err| Error::DeserializeError(err, body))?;
+        let tasks = list(&client(&transport), "79265d1a")
+            .await
+            .unwrap()
+            .into_body();
 
-    Ok(deserialized.tasks)
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(
+            transport.requests()[0].uri,
+            "https://example.org/v1/clusters/79265d1a/tasks"
+        );
+    }
 }
@@ -1,30 +1,105 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use super::super::error::Error;
+use super::super::schema::{join, CheckSchema, SchemaCheck};
 
 /// Status represents a enum with various task statuses.
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+///
+/// Values that are unknown to this version of the library are kept in `Other`
+/// when responses are deserialized, so the enum isn't `Copy`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
 pub enum Status {
     InProgress,
     Done,
     Error,
     Unknown,
+
+    /// Value that is unknown to this version of the library.
+    /// It never holds values of other variants, use `Status::from` to build it.
+    Other(String),
+}
+
+impl Status {
+    /// Get status value as it's returned by the MKS API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Status::InProgress => "IN_PROGRESS",
+            Status::Done => "DONE",
+            Status::Error => "ERROR",
+            Status::Unknown => "UNKNOWN",
+            Status::Other(status) => status,
+        }
+    }
+}
+
+/// Helpers to check the task lifecycle.
+impl Status {
+    /// Check if the task is in progress.
+    pub fn is_pending(&self) -> bool {
+        *self == Status::InProgress
+    }
+
+    /// Check if the task has finished successfully or with an error.
+    ///
+    /// Unknown statuses aren't terminal.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Status::Done | Status::Error)
+    }
+
+    /// Check if the task has failed.
+    pub fn is_error(&self) -> bool {
+        *self == Status::Error
+    }
 }
 
 impl std::fmt::Display for Status {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            Status::InProgress => "IN_PROGRESS".fmt(f),
-            Status::Done => "DONE".fmt(f),
-            Status::Error => "ERROR".fmt(f),
-            Status::Unknown => "UNKNOWN".fmt(f),
+        self.as_str().fmt(f)
+    }
+}
+
+impl From<String> for Status {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "IN_PROGRESS" => Status::InProgress,
+            "DONE" => Status::Done,
+            "ERROR" => Status::Error,
+            "UNKNOWN" => Status::Unknown,
+            _ => Status::Other(status),
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = Error;
+
+    /// Parse status case-insensitively, unknown values are rejected with `Error::ValueError`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Status::from(s.trim().to_ascii_uppercase()) {
+            Status::Other(_) => Err(Error::ValueError(String::from(s))),
+            status => Ok(status),
+        }
+    }
+}
+
+impl From<Status> for String {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Other(status) => status,
+            status => String::from(status.as_str()),
         }
     }
 }
 
 /// Type represents a enum with various task types.
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+///
+/// Values that are unknown to this version of the library are kept in `Other`
+/// when responses are deserialized, so the enum isn't `Copy`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
 pub enum Type {
     CreateCluster,
     DeleteCluster,
@@ -38,23 +113,76 @@ pub enum Type {
     UpgradeMastersConfiguration,
     UpgradeClusterConfiguration,
     Unknown,
+
+    /// Value that is unknown to this version of the library.
+    /// It never holds values of other variants, use `Type::from` to build it.
+    Other(String),
+}
+
+impl Type {
+    /// Get task type value as it's returned by the MKS API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Type::CreateCluster => "CREATE_CLUSTER",
+            Type::DeleteCluster => "DELETE_CLUSTER",
+            Type::RotateCerts => "ROTATE_CERTS",
+            Type::NodeGroupResize => "NODE_GROUP_RESIZE",
+            Type::NodeReinstall => "NODE_REINSTALL",
+            Type::ClusterResize => "CLUSTER_RESIZE",
+            Type::UpgradePatchVersion => "UPGRADE_PATCH_VERSION",
+            Type::UpgradeMinorVersion => "UPGRADE_MINOR_VERSION",
+            Type::UpdateNodegroupLabels => "UPDATE_NODEGROUP_LABELS",
+            Type::UpgradeMastersConfiguration => "UPGRADE_MASTERS_CONFIGURATION",
+            Type::UpgradeClusterConfiguration => "UPGRADE_CLUSTER_CONFIGURATION",
+            Type::Unknown => "UNKNOWN",
+            Type::Other(task_type) => task_type,
+        }
+    }
 }
 
 impl std::fmt::Display for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            Type::CreateCluster => "CREATE_CLUSTER".fmt(f),
-            Type::DeleteCluster => "DELETE_CLUSTER".fmt(f),
-            Type::RotateCerts => "ROTATE_CERTS".fmt(f),
-            Type::NodeGroupResize => "NODE_GROUP_RESIZE".fmt(f),
-            Type::NodeReinstall => "NODE_REINSTALL".fmt(f),
-            Type::ClusterResize => "CLUSTER_RESIZE".fmt(f),
-            Type::UpgradePatchVersion => "UPGRADE_PATCH_VERSION".fmt(f),
-            Type::UpgradeMinorVersion => "UPGRADE_MINOR_VERSION".fmt(f),
-            Type::UpdateNodegroupLabels => "UPDATE_NODEGROUP_LABELS".fmt(f),
-            Type::UpgradeMastersConfiguration => "UPGRADE_MASTERS_CONFIGURATION".fmt(f),
-            Type::UpgradeClusterConfiguration => "UPGRADE_CLUSTER_CONFIGURATION".fmt(f),
-            Type::Unknown => "UNKNOWN".fmt(f),
+        self.as_str().fmt(f)
+    }
+}
+
+impl From<String> for Type {
+    fn from(task_type: String) -> Self {
+        match task_type.as_str() {
+            "CREATE_CLUSTER" => Type::CreateCluster,
+            "DELETE_CLUSTER" => Type::DeleteCluster,
+            "ROTATE_CERTS" => Type::RotateCerts,
+            "NODE_GROUP_RESIZE" => Type::NodeGroupResize,
+            "NODE_REINSTALL" => Type::NodeReinstall,
+            "CLUSTER_RESIZE" => Type::ClusterResize,
+            "UPGRADE_PATCH_VERSION" => Type::UpgradePatchVersion,
+            "UPGRADE_MINOR_VERSION" => Type::UpgradeMinorVersion,
+            "UPDATE_NODEGROUP_LABELS" => Type::UpdateNodegroupLabels,
+            "UPGRADE_MASTERS_CONFIGURATION" => Type::UpgradeMastersConfiguration,
+            "UPGRADE_CLUSTER_CONFIGURATION" => Type::UpgradeClusterConfiguration,
+            "UNKNOWN" => Type::Unknown,
+            _ => Type::Other(task_type),
+        }
+    }
+}
+
+impl FromStr for Type {
+    type Err = Error;
+
+    /// Parse task type case-insensitively, unknown values are rejected with `Error::ValueError`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Type::from(s.trim().to_ascii_uppercase()) {
+            Type::Other(_) => Err(Error::ValueError(String::from(s))),
+            task_type => Ok(task_type),
+        }
+    }
+}
+
+impl From<Type> for String {
+    fn from(task_type: Type) -> Self {
+        match task_type {
+            Type::Other(task_type) => task_type,
+            task_type => String::from(task_type.as_str()),
         }
     }
 }
@@ -80,6 +208,11 @@ pub struct Task {
     /// Task type.
     #[serde(rename = "type")]
     pub task_type: Type,
+
+    /// Fields of the response that aren't modelled by this struct.
+    /// They are kept as is when the struct is serialized back.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// TaskRoot represents a root of a deserialized task.
@@ -93,3 +226,57 @@ pub struct TaskRoot {
 pub struct ListRoot {
     pub tasks: Vec<Task>,
 }
+
+impl CheckSchema for Task {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck) {
+        check.extra(field, &self.extra);
+        if let Status::Other(status) = &self.status {
+            check.unknown_value(&join(field, "status"), status);
+        }
+        if let Type::Other(task_type) = &self.task_type {
+            check.unknown_value(&join(field, "type"), task_type);
+        }
+    }
+}
+
+impl CheckSchema for TaskRoot {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck) {
+        self.task.check_schema(&join(field, "task"), check);
+    }
+}
+
+impl CheckSchema for ListRoot {
+    fn check_schema(&self, field: &str, check: &mut SchemaCheck) {
+        self.tasks.check_schema(&join(field, "tasks"), check);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_helpers() {
+        assert!(Status::InProgress.is_pending());
+        assert!(!Status::InProgress.is_terminal());
+        assert!(Status::Done.is_terminal());
+        assert!(!Status::Done.is_error());
+        assert!(Status::Error.is_terminal());
+        assert!(Status::Error.is_error());
+        assert!(!Status::Other(String::from("CANCELLED")).is_terminal());
+    }
+
+    #[test]
+    fn parse_task_type() {
+        assert_eq!(
+            "node_group_resize".parse::<Type>().unwrap(),
+            Type::NodeGroupResize
+        );
+        assert_eq!("done".parse::<Status>().unwrap(), Status::Done);
+        assert!(matches!(
+            "node_group_resze".parse::<Type>(),
+            Err(Error::ValueError(_))
+        ));
+        assert!(matches!("dne".parse::<Status>(), Err(Error::ValueError(_))));
+    }
+}
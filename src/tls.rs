@@ -0,0 +1,366 @@
+use hyper::client::HttpConnector;
+use hyper_proxy::ProxyConnector;
+#[cfg(feature = "rustls")]
+use std::sync::Arc;
+#[cfg(feature = "rustls")]
+use tokio_rustls::rustls;
+
+use super::error::Error;
+use super::HttpsConnector;
+
+// PEM labels of supported blocks.
+const CERTIFICATE_LABEL: &str = "CERTIFICATE";
+const PRIVATE_KEY_LABEL: &str = "PRIVATE KEY";
+
+/// `Certificate` represents trusted root certificates.
+#[derive(Clone, Debug)]
+pub struct Certificate {
+    ders: Vec<Vec<u8>>,
+}
+
+impl Certificate {
+    /// Parse PEM-encoded certificates.
+    ///
+    /// PEM can contain a bundle of several certificates.
+    pub fn from_pem(pem: &[u8]) -> Result<Certificate, Error> {
+        let ders = pem_blocks(pem, CERTIFICATE_LABEL)?;
+        if ders.is_empty() {
+            return Err(Error::TlsError("no certificates found in PEM".into()));
+        }
+
+        Ok(Certificate { ders })
+    }
+
+    /// Construct the new Certificate from a DER-encoded certificate.
+    pub fn from_der(der: &[u8]) -> Certificate {
+        Certificate {
+            ders: vec![der.to_vec()],
+        }
+    }
+}
+
+/// `Identity` represents a client certificate with its private key that is used for mTLS.
+#[derive(Clone)]
+pub struct Identity {
+    cert_pem: Vec<u8>,
+    key_pem: Vec<u8>,
+}
+
+impl std::fmt::Debug for Identity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Identity")
+            .field("cert_pem", &String::from_utf8_lossy(&self.cert_pem))
+            .finish()
+    }
+}
+
+impl Identity {
+    /// Parse PEM-encoded certificate chain and PKCS#8 private key.
+    ///
+    /// The first certificate in the chain must be the client one.
+    pub fn from_pem(cert_pem: &[u8], key_pem: &[u8]) -> Result<Identity, Error> {
+        if pem_blocks(cert_pem, CERTIFICATE_LABEL)?.is_empty() {
+            return Err(Error::TlsError("no certificates found in PEM".into()));
+        }
+        if pem_blocks(key_pem, PRIVATE_KEY_LABEL)?.len() != 1 {
+            return Err(Error::TlsError(
+                "PEM must contain a single PKCS#8 private key".into(),
+            ));
+        }
+
+        Ok(Identity {
+            cert_pem: cert_pem.to_vec(),
+            key_pem: key_pem.to_vec(),
+        })
+    }
+}
+
+// TlsConfig contains TLS settings of the Hyper connectors.
+#[derive(Clone, Debug)]
+pub(crate) struct TlsConfig {
+    pub(crate) root_certificates: Vec<Certificate>,
+    pub(crate) system_roots: bool,
+    pub(crate) identity: Option<Identity>,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self {
+            root_certificates: Vec::new(),
+            system_roots: true,
+            identity: None,
+        }
+    }
+}
+
+impl TlsConfig {
+    fn is_default(&self) -> bool {
+        self.root_certificates.is_empty() && self.system_roots && self.identity.is_none()
+    }
+
+    // Build the new HttpsConnector with the configured TLS settings.
+    pub(crate) fn https_connector(&self) -> Result<HttpsConnector, Error> {
+        if self.is_default() {
+            return Ok(HttpsConnector::new());
+        }
+
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+
+        #[cfg(feature = "rustls")]
+        {
+            let mut config = self.rustls_config()?;
+            config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+            Ok(HttpsConnector::from((http, config)))
+        }
+
+        #[cfg(feature = "rust-native-tls")]
+        {
+            let tls = tokio_tls::TlsConnector::from(self.native_tls_connector()?);
+            Ok(HttpsConnector::from((http, tls)))
+        }
+    }
+
+    // Build the new ProxyConnector that uses the configured TLS settings in tunnels.
+    pub(crate) fn proxy_connector(&self) -> Result<ProxyConnector<HttpsConnector>, Error> {
+        let connector = self.https_connector()?;
+        if self.is_default() {
+            return ProxyConnector::new(connector).map_err(Error::ConnectorError);
+        }
+
+        let mut proxy_connector = ProxyConnector::unsecured(connector);
+
+        #[cfg(feature = "rustls")]
+        proxy_connector.set_tls(Some(tokio_rustls::TlsConnector::from(Arc::new(
+            self.rustls_config()?,
+        ))));
+
+        #[cfg(feature = "rust-native-tls")]
+        proxy_connector.set_tls(Some(self.native_tls_connector()?));
+
+        Ok(proxy_connector)
+    }
+
+    #[cfg(feature = "rustls")]
+    fn rustls_config(&self) -> Result<rustls::ClientConfig, Error> {
+        let mut config = rustls::ClientConfig::new();
+
+        if self.system_roots {
+            config.root_store = match rustls_native_certs::load_native_certs() {
+                Ok(store) => store,
+                Err((Some(store), err)) => {
+                    tracing::warn!("could not load all system certificates: {}", err);
+                    store
+                }
+                Err((None, err)) => return Err(Error::TlsError(err.into())),
+            };
+        }
+
+        for der in self.root_certificates.iter().flat_map(|cert| &cert.ders) {
+            config
+                .root_store
+                .add(&rustls::Certificate(der.clone()))
+                .map_err(|err| {
+                    Error::TlsError(format!("bad root certificate: {:?}", err).into())
+                })?;
+        }
+
+        if let Some(identity) = &self.identity {
+            let chain = pem_blocks(&identity.cert_pem, CERTIFICATE_LABEL)?
+                .into_iter()
+                .map(rustls::Certificate)
+                .collect();
+            let key = pem_blocks(&identity.key_pem, PRIVATE_KEY_LABEL)?
+                .pop()
+                .map(rustls::PrivateKey)
+                .ok_or_else(|| Error::TlsError("no private key found in PEM".into()))?;
+
+            config
+                .set_single_client_cert(chain, key)
+                .map_err(|err| Error::TlsError(err.into()))?;
+        }
+
+        Ok(config)
+    }
+
+    #[cfg(feature = "rust-native-tls")]
+    fn native_tls_connector(&self) -> Result<native_tls::TlsConnector, Error> {
+        let mut builder = native_tls::TlsConnector::builder();
+        builder.disable_built_in_roots(!self.system_roots);
+
+        for der in self.root_certificates.iter().flat_map(|cert| &cert.ders) {
+            let cert = native_tls::Certificate::from_der(der)
+                .map_err(|err| Error::TlsError(err.into()))?;
+            builder.add_root_certificate(cert);
+        }
+
+        if let Some(identity) = &self.identity {
+            let identity = native_tls::Identity::from_pkcs8(&identity.cert_pem, &identity.key_pem)
+                .map_err(|err| Error::TlsError(err.into()))?;
+            builder.identity(identity);
+        }
+
+        builder.build().map_err(|err| Error::TlsError(err.into()))
+    }
+}
+
+// Decode all PEM blocks with the provided label.
+fn pem_blocks(pem: &[u8], label: &str) -> Result<Vec<Vec<u8>>, Error> {
+    let pem = std::str::from_utf8(pem).map_err(|err| Error::TlsError(err.into()))?;
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+
+    let mut blocks = Vec::new();
+    let mut rest = pem;
+    while let Some(start) = rest.find(&begin) {
+        rest = &rest[start + begin.len()..];
+        let stop = rest
+            .find(&end)
+            .ok_or_else(|| Error::TlsError(format!("unterminated {} block", label).into()))?;
+
+        let body: String = rest[..stop].split_whitespace().collect();
+        let der = base64::decode(&body).map_err(|err| Error::TlsError(err.into()))?;
+        blocks.push(der);
+
+        rest = &rest[stop + end.len()..];
+    }
+
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CA_PEM: &[u8] = include_bytes!("../tests/fixtures/tls/ca.pem");
+    const CLIENT_PEM: &[u8] = include_bytes!("../tests/fixtures/tls/client.pem");
+    const CLIENT_KEY: &[u8] = include_bytes!("../tests/fixtures/tls/client.key");
+    #[cfg(feature = "rustls")]
+    const SERVER_PEM: &[u8] = include_bytes!("../tests/fixtures/tls/server.pem");
+    #[cfg(feature = "rustls")]
+    const SERVER_KEY: &[u8] = include_bytes!("../tests/fixtures/tls/server.key");
+
+    #[test]
+    fn certificate_bundle() {
+        let bundle = [CA_PEM, CLIENT_PEM].concat();
+
+        let cert = Certificate::from_pem(&bundle).unwrap();
+
+        assert_eq!(cert.ders.len(), 2);
+    }
+
+    #[test]
+    fn bad_certificate() {
+        assert!(matches!(
+            Certificate::from_pem(b"not a certificate"),
+            Err(Error::TlsError(_))
+        ));
+        assert!(matches!(
+            Certificate::from_pem(b"-----BEGIN CERTIFICATE-----\nAAAA"),
+            Err(Error::TlsError(_))
+        ));
+        assert!(matches!(
+            Identity::from_pem(CLIENT_PEM, CLIENT_PEM),
+            Err(Error::TlsError(_))
+        ));
+    }
+
+    #[test]
+    fn custom_connectors() {
+        let tls = TlsConfig {
+            root_certificates: vec![Certificate::from_pem(CA_PEM).unwrap()],
+            system_roots: false,
+            identity: Some(Identity::from_pem(CLIENT_PEM, CLIENT_KEY).unwrap()),
+        };
+
+        assert!(tls.https_connector().is_ok());
+        assert!(tls.proxy_connector().is_ok());
+        assert!(!format!("{:?}", tls.identity).contains("PRIVATE KEY"));
+    }
+
+    // Accept a single mTLS connection and reply to its request.
+    // Returns the number of certificates presented by the client.
+    #[cfg(feature = "rustls")]
+    async fn mtls_server() -> (u16, tokio::task::JoinHandle<Option<usize>>) {
+        use rustls::{AllowAnyAuthenticatedClient, RootCertStore, ServerConfig, Session};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut roots = RootCertStore::empty();
+        for der in Certificate::from_pem(CA_PEM).unwrap().ders {
+            roots.add(&rustls::Certificate(der)).unwrap();
+        }
+        let mut config = ServerConfig::new(AllowAnyAuthenticatedClient::new(roots));
+        let chain = pem_blocks(SERVER_PEM, CERTIFICATE_LABEL).unwrap();
+        let key = pem_blocks(SERVER_KEY, PRIVATE_KEY_LABEL).unwrap().remove(0);
+        config
+            .set_single_cert(
+                chain.into_iter().map(rustls::Certificate).collect(),
+                rustls::PrivateKey(key),
+            )
+            .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+
+        let mut listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(_) => return None,
+            };
+
+            let mut buf = vec![0; 4096];
+            let n = stream.read(&mut buf).await.unwrap();
+            assert!(buf[..n].starts_with(b"GET /v1/kubeversions "));
+            let body = r#"{"kube_versions": []}"#;
+            let resp = format!(
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(resp.as_bytes()).await.unwrap();
+
+            stream.get_ref().1.get_peer_certificates().map(|c| c.len())
+        });
+
+        (port, handle)
+    }
+
+    #[cfg(feature = "rustls")]
+    #[tokio::test]
+    async fn request_with_private_ca_and_client_certificate() {
+        let (port, server) = mtls_server().await;
+
+        let client = crate::Client::builder()
+            .with_env_proxy(false)
+            .with_root_certificate(Certificate::from_pem(CA_PEM).unwrap())
+            .with_system_roots(false)
+            .with_identity(Identity::from_pem(CLIENT_PEM, CLIENT_KEY).unwrap())
+            .with_retry_policy(crate::retry::RetryPolicy::none())
+            .build(&format!("https://localhost:{}", port), "token_a")
+            .unwrap();
+
+        let kube_versions = client.list_kube_versions().await.unwrap();
+        assert!(kube_versions.is_empty());
+        assert_eq!(server.await.unwrap(), Some(1));
+    }
+
+    #[cfg(feature = "rustls")]
+    #[tokio::test]
+    async fn request_with_untrusted_server_certificate() {
+        let (port, server) = mtls_server().await;
+
+        let client = crate::Client::builder()
+            .with_env_proxy(false)
+            .with_system_roots(false)
+            .with_identity(Identity::from_pem(CLIENT_PEM, CLIENT_KEY).unwrap())
+            .with_retry_policy(crate::retry::RetryPolicy::none())
+            .build(&format!("https://localhost:{}", port), "token_a")
+            .unwrap();
+
+        let err = client.list_kube_versions().await.unwrap_err();
+        assert!(matches!(err, Error::HyperError(_)));
+        assert_eq!(server.await.unwrap(), None);
+    }
+}
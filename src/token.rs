@@ -0,0 +1,256 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tokio::process::Command;
+
+use super::error::Error;
+
+/// Future that resolves into a token of the `TokenProvider`.
+pub type TokenFuture<'a> = Pin<Box<dyn Future<Output = Result<String, Error>> + Send + 'a>>;
+
+/// Future that resolves into a refreshed token or `None` if the provider has no new token.
+pub type RefreshFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Option<String>, Error>> + Send + 'a>>;
+
+/// `TokenProvider` supplies tokens that are used to authenticate requests.
+///
+/// `token` is called before each request attempt so providers should cache
+/// tokens themselves. `refresh` is called once with the rejected token when the MKS API
+/// responds with 401 status code, the request is retried with the refreshed token
+/// unless the provider has no new token or returns the rejected one.
+/// Concurrent requests can be rejected with the same token, so providers can return
+/// a token that has already replaced the rejected one instead of getting another one.
+pub trait TokenProvider: Send + Sync {
+    /// Get the current token.
+    fn token(&self) -> TokenFuture<'_>;
+
+    /// Get a new token after the provided one has been rejected.
+    fn refresh<'a>(&'a self, rejected: &'a str) -> RefreshFuture<'a>;
+}
+
+impl<T: TokenProvider + ?Sized> TokenProvider for Arc<T> {
+    fn token(&self) -> TokenFuture<'_> {
+        (**self).token()
+    }
+
+    fn refresh<'a>(&'a self, rejected: &'a str) -> RefreshFuture<'a> {
+        (**self).refresh(rejected)
+    }
+}
+
+/// `StaticToken` always provides the same token.
+///
+/// It can't be refreshed so requests with a rejected token aren't repeated.
+#[derive(Clone)]
+pub struct StaticToken {
+    token: String,
+}
+
+impl std::fmt::Debug for StaticToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaticToken").finish()
+    }
+}
+
+impl StaticToken {
+    /// Construct the new StaticToken.
+    pub fn new(token: &str) -> Result<StaticToken, Error> {
+        Ok(StaticToken {
+            token: check_token(token)?,
+        })
+    }
+}
+
+impl TokenProvider for StaticToken {
+    fn token(&self) -> TokenFuture<'_> {
+        Box::pin(async move { Ok(self.token.clone()) })
+    }
+
+    fn refresh<'a>(&'a self, _rejected: &'a str) -> RefreshFuture<'a> {
+        Box::pin(async { Ok(None) })
+    }
+}
+
+/// `FileToken` reads a token from the file.
+///
+/// The file is read on the first request and again on every refresh, so it
+/// can be updated by an external process. Surrounding whitespace is trimmed.
+pub struct FileToken {
+    path: PathBuf,
+    cached: Mutex<Option<String>>,
+}
+
+impl std::fmt::Debug for FileToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileToken")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl FileToken {
+    /// Construct the new FileToken.
+    pub fn new<P: Into<PathBuf>>(path: P) -> FileToken {
+        FileToken {
+            path: path.into(),
+            cached: Mutex::new(None),
+        }
+    }
+
+    async fn read(&self) -> Result<String, Error> {
+        let content = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|err| Error::TokenError(err.into()))?;
+        let token = check_token(&content)?;
+        *self.cached.lock().unwrap() = Some(token.clone());
+
+        Ok(token)
+    }
+}
+
+impl TokenProvider for FileToken {
+    fn token(&self) -> TokenFuture<'_> {
+        Box::pin(async move {
+            let cached = self.cached.lock().unwrap().clone();
+            match cached {
+                Some(token) => Ok(token),
+                None => self.read().await,
+            }
+        })
+    }
+
+    fn refresh<'a>(&'a self, _rejected: &'a str) -> RefreshFuture<'a> {
+        Box::pin(async move { self.read().await.map(Some) })
+    }
+}
+
+/// `CommandToken` gets a token from the standard output of a command.
+///
+/// The command is run on the first request and again on every refresh.
+/// Surrounding whitespace is trimmed.
+pub struct CommandToken {
+    program: String,
+    args: Vec<String>,
+    cached: Mutex<Option<String>>,
+}
+
+impl std::fmt::Debug for CommandToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandToken")
+            .field("program", &self.program)
+            .field("args", &self.args)
+            .finish()
+    }
+}
+
+impl CommandToken {
+    /// Construct the new CommandToken.
+    pub fn new(program: &str, args: &[&str]) -> CommandToken {
+        CommandToken {
+            program: String::from(program),
+            args: args.iter().map(|arg| String::from(*arg)).collect(),
+            cached: Mutex::new(None),
+        }
+    }
+
+    async fn run(&self) -> Result<String, Error> {
+        let output = Command::new(&self.program)
+            .args(&self.args)
+            .output()
+            .await
+            .map_err(|err| Error::TokenError(err.into()))?;
+        if !output.status.success() {
+            return Err(Error::TokenError(
+                format!(
+                    "command {} failed with {}: {}",
+                    self.program,
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )
+                .into(),
+            ));
+        }
+
+        let token = check_token(&String::from_utf8_lossy(&output.stdout))?;
+        *self.cached.lock().unwrap() = Some(token.clone());
+
+        Ok(token)
+    }
+}
+
+impl TokenProvider for CommandToken {
+    fn token(&self) -> TokenFuture<'_> {
+        Box::pin(async move {
+            let cached = self.cached.lock().unwrap().clone();
+            match cached {
+                Some(token) => Ok(token),
+                None => self.run().await,
+            }
+        })
+    }
+
+    fn refresh<'a>(&'a self, _rejected: &'a str) -> RefreshFuture<'a> {
+        Box::pin(async move { self.run().await.map(Some) })
+    }
+}
+
+// Trim the token and check that it's not empty.
+fn check_token(token: &str) -> Result<String, Error> {
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(Error::EmptyTokenError);
+    }
+
+    Ok(String::from(token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[tokio::test]
+    async fn static_token() {
+        let provider = StaticToken::new("token_a").unwrap();
+
+        assert_eq!(provider.token().await.unwrap(), "token_a");
+        assert_eq!(provider.refresh("token_a").await.unwrap(), None);
+        assert!(matches!(StaticToken::new(""), Err(Error::EmptyTokenError)));
+        assert!(!format!("{:?}", provider).contains("token_a"));
+    }
+
+    #[tokio::test]
+    async fn file_token() {
+        let path = std::env::temp_dir().join(format!("mks-token-{}", std::process::id()));
+        std::fs::write(&path, "token_a\n").unwrap();
+        let provider = FileToken::new(&path);
+
+        assert_eq!(provider.token().await.unwrap(), "token_a");
+
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(b"token_b").unwrap();
+        assert_eq!(provider.token().await.unwrap(), "token_a");
+        assert_eq!(
+            provider.refresh("token_a").await.unwrap(),
+            Some(String::from("token_b"))
+        );
+        assert_eq!(provider.token().await.unwrap(), "token_b");
+        assert!(!format!("{:?}", provider).contains("token_b"));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            provider.refresh("token_b").await,
+            Err(Error::TokenError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn command_token() {
+        let provider = CommandToken::new("echo", &["token_a"]);
+        assert_eq!(provider.token().await.unwrap(), "token_a");
+
+        let provider = CommandToken::new("false", &[]);
+        assert!(matches!(provider.token().await, Err(Error::TokenError(_))));
+    }
+}
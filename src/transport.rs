@@ -0,0 +1,170 @@
+use hyper::body::Bytes;
+use hyper::header::HeaderMap;
+use hyper::{Body, Method, Request, Response, StatusCode, Uri};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use super::error::Error;
+use super::HttpsConnector;
+
+/// Future that resolves into a response of the `Transport`.
+pub type ResponseFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Response<Body>, Error>> + Send + 'a>>;
+
+/// `Transport` sends prepared requests to the MKS API.
+///
+/// `HyperTransport` is used by default. Implement this trait to use
+/// another HTTP stack or to mock the MKS API in tests.
+pub trait Transport: Send + Sync {
+    /// Send a request and return its response.
+    fn send(&self, req: Request<Body>) -> ResponseFuture<'_>;
+}
+
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    fn send(&self, req: Request<Body>) -> ResponseFuture<'_> {
+        (**self).send(req)
+    }
+}
+
+/// `HyperTransport` sends requests with the Hyper client.
+pub struct HyperTransport {
+    client: hyper::Client<HttpsConnector>,
+}
+
+impl HyperTransport {
+    /// Construct the new HyperTransport with a new HttpsConnector.
+    ///
+    /// It will use hyper_rustls or hyper_tls depending on selected library features.
+    pub fn new() -> HyperTransport {
+        HyperTransport::from_client(hyper::Client::builder().build(HttpsConnector::new()))
+    }
+
+    /// Construct the new HyperTransport with the provided Hyper client.
+    pub fn from_client(client: hyper::Client<HttpsConnector>) -> HyperTransport {
+        HyperTransport { client }
+    }
+}
+
+impl Default for HyperTransport {
+    fn default() -> Self {
+        HyperTransport::new()
+    }
+}
+
+impl Transport for HyperTransport {
+    fn send(&self, req: Request<Body>) -> ResponseFuture<'_> {
+        Box::pin(async move { Ok(self.client.request(req).await?) })
+    }
+}
+
+/// RecordedRequest represents a request received by the `InMemoryTransport`.
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    /// Request method.
+    pub method: Method,
+
+    /// Request URI.
+    pub uri: Uri,
+
+    /// Request headers.
+    pub headers: HeaderMap,
+
+    /// Request body.
+    pub body: Bytes,
+}
+
+/// `InMemoryTransport` replies with prepared responses without any network calls.
+///
+/// Responses are returned in the same order they were added. All received
+/// requests are recorded and can be inspected afterwards.
+#[derive(Default)]
+pub struct InMemoryTransport {
+    responses: Mutex<VecDeque<Response<Bytes>>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl InMemoryTransport {
+    /// Construct the new InMemoryTransport without responses.
+    pub fn new() -> InMemoryTransport {
+        InMemoryTransport::default()
+    }
+
+    /// Add a response with the provided status code and body.
+    pub fn with_response(self, status: u16, body: &str) -> InMemoryTransport {
+        let mut resp = Response::new(Bytes::from(body.to_string()));
+        *resp.status_mut() = StatusCode::from_u16(status).expect("invalid status code");
+
+        self.with_http_response(resp)
+    }
+
+    /// Add a complete HTTP response.
+    pub fn with_http_response(self, resp: Response<Bytes>) -> InMemoryTransport {
+        self.push_response(resp);
+        self
+    }
+
+    /// Add a complete HTTP response to the already shared transport.
+    pub fn push_response(&self, resp: Response<Bytes>) {
+        self.responses.lock().unwrap().push_back(resp);
+    }
+
+    /// Get all requests received by the transport.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for InMemoryTransport {
+    fn send(&self, req: Request<Body>) -> ResponseFuture<'_> {
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+
+            self.requests.lock().unwrap().push(RecordedRequest {
+                method: parts.method,
+                uri: parts.uri,
+                headers: parts.headers,
+                body,
+            });
+
+            let resp = self
+                .responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .ok_or_else(|| Error::TransportError("no responses left".into()))?;
+
+            Ok(resp.map(Body::from))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn in_memory_transport() {
+        let transport = InMemoryTransport::new()
+            .with_response(200, "first")
+            .with_response(404, "second");
+
+        let req = Request::new(Body::from("request"));
+        let resp = transport.send(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        assert_eq!(body, "first");
+
+        let resp = transport.send(Request::new(Body::empty())).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let err = transport.send(Request::new(Body::empty())).await;
+        assert!(matches!(err, Err(Error::TransportError(_))));
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].body, "request");
+    }
+}
@@ -1,8 +1,8 @@
 use retry::delay::Fixed;
 use retry::retry;
+use selectel_mks::blocking::Client;
 use selectel_mks::cluster;
 use selectel_mks::error::Error;
-use selectel_mks::Client;
 
 const CLUSTER_CREATE_INTERVAL: u64 = 10_000;
 const CLUSTER_CREATE_RETRIES: usize = 60;
@@ -75,10 +75,10 @@ pub fn wait_for_cluster_active_status_or_panic(
                 }
             },
             Err(e) => match e {
-                Error::HttpError(status, _) => {
+                Error::HttpError(err) => {
                     let msg = format!(
-                        "got unexpected HTTP status code {} while getting status of the cluster {}",
-                        status, cluster_id
+                        "got unexpected HTTP error \"{}\" while getting status of the cluster {}",
+                        err, cluster_id
                     );
                     println!("{}", msg);
 
@@ -116,18 +116,16 @@ pub fn wait_for_cluster_deletion_or_panic(
         Fixed::from_millis(interval).take(retries),
         || match client.get_cluster(cluster_id) {
             Err(e) => match e {
-                Error::HttpError(status, _) => match status {
-                    404 => Ok(()),
-                    _ => {
-                        let msg = format!(
-                            "got unexpected HTTP status code {} while getting status of the cluster {}",
-                            status, cluster_id
-                        );
-                        println!("{}", msg);
-
-                        Err(msg)
-                    }
-                },
+                Error::HttpError(ref err) if err.is_not_found() => Ok(()),
+                Error::HttpError(err) => {
+                    let msg = format!(
+                        "got unexpected HTTP error \"{}\" while getting status of the cluster {}",
+                        err, cluster_id
+                    );
+                    println!("{}", msg);
+
+                    Err(msg)
+                }
                 _ => {
                     let msg = format!(
                         "got unexpected error {:?} while getting status of the cluster {}",
@@ -145,5 +143,6 @@ pub fn wait_for_cluster_deletion_or_panic(
                 Err(msg)
             }
         },
-    ).expect("unable to wait for cluster to become deleted");
+    )
+    .expect("unable to wait for cluster to become deleted");
 }
@@ -2,7 +2,8 @@ pub mod cluster_common;
 pub mod node_common;
 pub mod nodegroup_common;
 
-use selectel_mks::Client;
+use selectel_mks::blocking::Client;
+use selectel_mks::config::Profile;
 use std::env;
 
 // Env variable to turn integration testing on or off.
@@ -33,8 +34,13 @@ pub fn setup() -> Client {
         )
     });
 
-    Client::new(base_endpoint.as_str(), token.as_str())
-        .expect("Failed to initialize MKS client for tests")
+    Profile {
+        endpoint: Some(base_endpoint),
+        auth_token: Some(token),
+        ..Profile::default()
+    }
+    .build_blocking()
+    .expect("Failed to initialize MKS client for tests")
 }
 
 /// Check if integration tests are enabled.
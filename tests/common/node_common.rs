@@ -1,5 +1,5 @@
 use super::cluster_common;
-use selectel_mks::Client;
+use selectel_mks::blocking::Client;
 
 const NODE_REINSTALL_INTERVAL: u64 = 10_000;
 const NODE_REINSTALL_RETRIES: usize = 60;
@@ -1,6 +1,6 @@
 use super::cluster_common;
+use selectel_mks::blocking::Client;
 use selectel_mks::nodegroup;
-use selectel_mks::Client;
 
 const NODEGROUP_CREATE_INTERVAL: u64 = 10_000;
 const NODEGROUP_CREATE_RETRIES: usize = 60;